@@ -16,20 +16,20 @@ fn main() {
     let program = RcxProgram {
         rules: vec![
             // PING → PONG (rewrite), then treat the result as stable (r_a)
-            RcxRule {
-                pattern: Mu::Sym("PING".into()),
-                action: RuleAction::Rewrite(Mu::Sym("PONG".into())),
-            },
+            RcxRule::new(
+                Mu::Sym("PING".into()),
+                RuleAction::Rewrite(Mu::Sym("PONG".into())),
+            ),
             // Explicit lobe pattern
-            RcxRule {
-                pattern: Mu::Node(vec![Mu::Sym("U".into()), Mu::Sym("U".into())]),
-                action: RuleAction::ToLobe,
-            },
+            RcxRule::new(
+                Mu::Node(vec![Mu::Sym("U".into()), Mu::Sym("U".into())]),
+                RuleAction::ToLobe,
+            ),
             // Explicit sink pattern
-            RcxRule {
-                pattern: Mu::Node(vec![Mu::Sym("U".into()), Mu::Sym("V".into())]),
-                action: RuleAction::ToSink,
-            },
+            RcxRule::new(
+                Mu::Node(vec![Mu::Sym("U".into()), Mu::Sym("V".into())]),
+                RuleAction::ToSink,
+            ),
         ],
     };
 
@@ -0,0 +1,45 @@
+use std::env;
+use std::path::Path;
+
+use rcx_pi_rust::harness;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let mut dir = "tests/cases".to_string();
+    let mut bless = false;
+    for arg in &args {
+        match arg.as_str() {
+            "--bless" => bless = true,
+            other => dir = other.to_string(),
+        }
+    }
+
+    let dir = Path::new(&dir);
+
+    if bless {
+        match harness::bless_dir(dir) {
+            Ok(n) => {
+                println!("[bless] rewrote {n} case file(s) under {}", dir.display());
+            }
+            Err(e) => {
+                eprintln!("[bless] error: {e}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    match harness::run_dir(dir) {
+        Ok(report) => {
+            print!("{report}");
+            if !report.all_passed() {
+                std::process::exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("[harness] error: {e}");
+            std::process::exit(1);
+        }
+    }
+}
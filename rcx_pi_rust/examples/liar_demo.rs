@@ -1,5 +1,6 @@
 use rcx_pi_rust::{
-    engine::Engine, mu_loader::load_mu_file, parser::parse_mu, state::RCXState, trace::RouteKind,
+    engine::Engine, mu_loader::load_mu_file, parser::parse_mu, state::RCXState,
+    trace::RouteKind, trace_canon::trace_to_canon_jsonl,
 };
 
 fn main() {
@@ -51,4 +52,7 @@ fn main() {
             evt.step_index, evt.phase, evt.route, evt.payload
         );
     }
+
+    println!("=== Canonical trace (JSONL) ===");
+    print!("{}", trace_to_canon_jsonl(&state.trace));
 }
@@ -0,0 +1,71 @@
+use std::env;
+use std::path::Path;
+
+use rcx_pi_rust::{
+    engine::Engine,
+    metrics::{merge_metrics, RunMetrics},
+    mu_loader::load_mu_file,
+    orbit::orbit_with_provenance,
+    parser::parse_mu,
+    state::RCXState,
+    types::{Mu, RcxProgram},
+};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() < 4 {
+        eprintln!("usage:");
+        eprintln!("  cargo run --example metrics_cli -- <world> <Mu> <label> [max_steps]");
+        eprintln!();
+        eprintln!("examples:");
+        eprintln!("  cargo run --example metrics_cli -- pingpong ping nightly-2026-07-28 12");
+        eprintln!("  cargo run --example metrics_cli -- rcx_core \"[omega,[a,b]]\" $(git rev-parse --short HEAD)");
+        std::process::exit(1);
+    }
+
+    let world_name = &args[1];
+    let mu_src = &args[2];
+    let label = &args[3];
+    let max_steps: usize = if args.len() >= 5 {
+        args[4].parse().unwrap_or(12)
+    } else {
+        12
+    };
+
+    let program: RcxProgram = match load_mu_file(world_name) {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("[world] error loading {world_name}: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let seed: Mu = match parse_mu(mu_src) {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("[ω] parse error for {mu_src}: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let (seq, prov) = orbit_with_provenance(&program, seed.clone(), max_steps);
+
+    let mut engine = Engine::new(program);
+    let mut state = RCXState::new();
+    let _ = engine.process_input(&mut state, seed);
+
+    let run_metrics = RunMetrics::from_run(&state, &seq, &prov);
+
+    let path = Path::new("metrics/metrics.json");
+    if let Err(e) = merge_metrics(path, world_name, label, &run_metrics) {
+        eprintln!("[metrics] error merging {}: {e}", path.display());
+        std::process::exit(1);
+    }
+
+    println!(
+        "[metrics] {world_name}@{label}: ra={} lobes={} sink={} orbit_len={} cycle={:?}",
+        run_metrics.ra_count, run_metrics.lobes_count, run_metrics.sink_count, run_metrics.orbit_len, run_metrics.cycle
+    );
+    println!("[metrics] merged into {}", path.display());
+}
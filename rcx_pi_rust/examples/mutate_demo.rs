@@ -21,18 +21,12 @@ fn wildcard_variants(rule: &RcxRule) -> Vec<RcxRule> {
                 // Variant 1: wildcard in slot 0 -> [_, b]
                 let mut v1_children = children.clone();
                 v1_children[0] = Mu::Sym("_".to_string());
-                out.push(RcxRule {
-                    pattern: Mu::Node(v1_children),
-                    action: rule.action.clone(),
-                });
+                out.push(RcxRule::new(Mu::Node(v1_children), rule.action.clone()));
 
                 // Variant 2: wildcard in slot 1 -> [a, _]
                 let mut v2_children = children.clone();
                 v2_children[1] = Mu::Sym("_".to_string());
-                out.push(RcxRule {
-                    pattern: Mu::Node(v2_children),
-                    action: rule.action.clone(),
-                });
+                out.push(RcxRule::new(Mu::Node(v2_children), rule.action.clone()));
             }
         }
     }
@@ -63,6 +57,7 @@ fn main() {
             RuleAction::ToLobe => "lobe".to_string(),
             RuleAction::ToSink => "sink".to_string(),
             RuleAction::Rewrite(mu) => format!("rewrite({})", mu_to_string(mu)),
+            RuleAction::RewriteTemplate(mu) => format!("unify({})", mu_to_string(mu)),
         };
         println!("  {idx}: {pat} -> {action}");
     }
@@ -82,6 +77,7 @@ fn main() {
                 RuleAction::ToLobe => "lobe".to_string(),
                 RuleAction::ToSink => "sink".to_string(),
                 RuleAction::Rewrite(mu) => format!("rewrite({})", mu_to_string(mu)),
+                RuleAction::RewriteTemplate(mu) => format!("unify({})", mu_to_string(mu)),
             };
             println!("    variant {vidx}: {pat} -> {action}");
         }
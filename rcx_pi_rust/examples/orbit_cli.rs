@@ -3,73 +3,11 @@ use std::env;
 use rcx_pi_rust::{
     formatter::mu_to_string,
     mu_loader::load_mu_file,
-    orbit::orbit,
+    orbit::{classify, orbit, step},
     parser::parse_mu,
     types::{Mu, RcxProgram},
 };
 
-/// Classify an orbit sequence into a simple ω-limit description.
-/// This is a copy of the REPL's local classifier, but made standalone for the CLI.
-fn classify_orbit(seq: &[Mu]) -> String {
-    if seq.is_empty() {
-        return "empty orbit (no states produced)".to_string();
-    }
-    if seq.len() == 1 {
-        return "no detected cycle up to 1 steps".to_string();
-    }
-
-    // 1) Try "pure cycle from the seed" detection.
-    let seed = &seq[0];
-    let mut found_period: Option<usize> = None;
-
-    for i in 1..seq.len() {
-        if &seq[i] == seed {
-            found_period = Some(i);
-            break;
-        }
-    }
-
-    if let Some(period) = found_period {
-        // Verify that the whole sequence respects this period.
-        let mut pure = true;
-        for (idx, mu) in seq.iter().enumerate() {
-            if mu != &seq[idx % period] {
-                pure = false;
-                break;
-            }
-        }
-
-        if pure {
-            if period == 1 {
-                return "fixed point".to_string();
-            } else {
-                return format!("pure limit cycle (period = {period})");
-            }
-        }
-    }
-
-    // 2) Fallback: transient + cycle detection using last state (your current logic, but with rposition).
-    let last = &seq[seq.len() - 1];
-
-    if let Some(prev_idx) = seq[..seq.len() - 1].iter().rposition(|m| m == last) {
-        let transient_len = prev_idx;
-        let period = seq.len() - 1 - prev_idx;
-
-        if period == 1 {
-            if transient_len == 0 {
-                "fixed point".to_string()
-            } else {
-                format!("transient of length {transient_len} then fixed point")
-            }
-        } else if transient_len == 0 {
-            format!("pure limit cycle (period = {period})")
-        } else {
-            format!("transient of length {transient_len} then limit cycle (period = {period})")
-        }
-    } else {
-        format!("no detected cycle up to {} steps", seq.len())
-    }
-}
 fn main() {
     let args: Vec<String> = env::args().collect();
 
@@ -128,7 +66,7 @@ fn main() {
         println!("  {:>3}: {}", i, mu_to_string(m));
     }
 
-    let classification = classify_orbit(&seq);
+    let classification = classify(&seed, max_steps, |current| step(&program, current));
     println!();
     println!("[ω] classification: {}", classification);
 }
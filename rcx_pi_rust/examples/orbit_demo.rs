@@ -1,5 +1,6 @@
 use rcx_pi_rust::{
     formatter::mu_to_string,
+    rewrite_graph::{self, RewriteGraph},
     types::{Mu, RcxProgram, RcxRule, RuleAction},
 };
 
@@ -39,6 +40,36 @@ fn run_orbit(program: &RcxProgram, seed: Mu, max_steps: usize) -> Vec<Mu> {
     seq
 }
 
+/// Print a `RewriteGraph`'s nodes - classifying each as a normal form, a
+/// cycle-closing back-edge source, or neither - followed by the shortest
+/// rewrite path to `target`, if the graph reached it.
+fn print_graph(graph: &RewriteGraph, target: &Mu) {
+    println!("  nodes ({}):", graph.order.len());
+    for (i, term) in graph.order.iter().enumerate() {
+        let kind = if graph.edges[i].is_empty() {
+            "normal form"
+        } else if graph.edges[i].iter().any(|e| e.back_edge) {
+            "closes a cycle"
+        } else {
+            "internal"
+        };
+        println!("    {i:>3}: {} ({kind})", mu_to_string(term));
+    }
+    if graph.truncated {
+        println!("  (truncated: max_nodes reached before the frontier emptied)");
+    }
+
+    match graph.shortest_path(target) {
+        Some(steps) => {
+            println!("  shortest path to {}:", mu_to_string(target));
+            for (rule_i, term) in steps {
+                println!("    --[rule {rule_i}]--> {}", mu_to_string(&term));
+            }
+        }
+        None => println!("  {} is not reachable from the seed", mu_to_string(target)),
+    }
+}
+
 fn main() {
     // Tiny "ω-orbit" toy program:
     //
@@ -54,31 +85,31 @@ fn main() {
     let program = RcxProgram {
         rules: vec![
             // X -> [X,X]
-            RcxRule {
-                pattern: Mu::Sym("X".to_string()),
-                action: RuleAction::Rewrite(Mu::Node(vec![
+            RcxRule::new(
+                Mu::Sym("X".to_string()),
+                RuleAction::Rewrite(Mu::Node(vec![
                     Mu::Sym("X".to_string()),
                     Mu::Sym("X".to_string()),
                 ])),
-            },
+            ),
             // [X,X] -> [X,X,X]
-            RcxRule {
-                pattern: Mu::Node(vec![Mu::Sym("X".to_string()), Mu::Sym("X".to_string())]),
-                action: RuleAction::Rewrite(Mu::Node(vec![
+            RcxRule::new(
+                Mu::Node(vec![Mu::Sym("X".to_string()), Mu::Sym("X".to_string())]),
+                RuleAction::Rewrite(Mu::Node(vec![
                     Mu::Sym("X".to_string()),
                     Mu::Sym("X".to_string()),
                     Mu::Sym("X".to_string()),
                 ])),
-            },
+            ),
             // [X,X,X] -> STABLE
-            RcxRule {
-                pattern: Mu::Node(vec![
+            RcxRule::new(
+                Mu::Node(vec![
                     Mu::Sym("X".to_string()),
                     Mu::Sym("X".to_string()),
                     Mu::Sym("X".to_string()),
                 ]),
-                action: RuleAction::Rewrite(Mu::Sym("STABLE".to_string())),
-            },
+                RuleAction::Rewrite(Mu::Sym("STABLE".to_string())),
+            ),
         ],
     };
 
@@ -97,4 +128,37 @@ fn main() {
         }
         println!();
     }
+
+    // `run_orbit` above only ever follows the first matching rule, so a
+    // nondeterministic program - one term with more than one applicable
+    // Rewrite rule - silently hides its other branches, and a cycle among
+    // them just keeps it rewriting until `max_steps` runs out, without
+    // reporting anything. The rewrite graph explores every branch
+    // breadth-first instead:
+    //
+    //   ping -> pong | done
+    //   pong -> ping
+    //
+    // so `ping` and `pong` form a cycle, `done` is a normal form, and the
+    // graph still finds the shortest way to it.
+    let graph_program = RcxProgram {
+        rules: vec![
+            RcxRule::new(
+                Mu::Sym("ping".to_string()),
+                RuleAction::Rewrite(Mu::Sym("pong".to_string())),
+            ),
+            RcxRule::new(
+                Mu::Sym("ping".to_string()),
+                RuleAction::Rewrite(Mu::Sym("done".to_string())),
+            ),
+            RcxRule::new(
+                Mu::Sym("pong".to_string()),
+                RuleAction::Rewrite(Mu::Sym("ping".to_string())),
+            ),
+        ],
+    };
+
+    println!("=== Rewrite graph from ping ===");
+    let graph = rewrite_graph::explore(&graph_program, Mu::Sym("ping".to_string()), 50);
+    print_graph(&graph, &Mu::Sym("done".to_string()));
 }
@@ -23,18 +23,9 @@ fn main() {
     // Anything else falls back to the existing structural classifier.
     let program = RcxProgram {
         rules: vec![
-            RcxRule {
-                pattern: news_node(&["NEWS", "STABLE"]),
-                action: RuleAction::ToRa,
-            },
-            RcxRule {
-                pattern: news_node(&["NEWS", "UNSTABLE"]),
-                action: RuleAction::ToLobe,
-            },
-            RcxRule {
-                pattern: news_node(&["NEWS", "PARADOX"]),
-                action: RuleAction::ToSink,
-            },
+            RcxRule::new(news_node(&["NEWS", "STABLE"]), RuleAction::ToRa),
+            RcxRule::new(news_node(&["NEWS", "UNSTABLE"]), RuleAction::ToLobe),
+            RcxRule::new(news_node(&["NEWS", "PARADOX"]), RuleAction::ToSink),
         ],
     };
 
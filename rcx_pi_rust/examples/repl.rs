@@ -44,27 +44,27 @@ fn classify_orbit(seq: &[Mu]) -> String {
 fn default_news_program() -> RcxProgram {
     RcxProgram {
         rules: vec![
-            RcxRule {
-                pattern: Mu::Node(vec![
+            RcxRule::new(
+                Mu::Node(vec![
                     Mu::Sym("news".to_string()),
                     Mu::Sym("stable".to_string()),
                 ]),
-                action: RuleAction::ToRa,
-            },
-            RcxRule {
-                pattern: Mu::Node(vec![
+                RuleAction::ToRa,
+            ),
+            RcxRule::new(
+                Mu::Node(vec![
                     Mu::Sym("news".to_string()),
                     Mu::Sym("unstable".to_string()),
                 ]),
-                action: RuleAction::ToLobe,
-            },
-            RcxRule {
-                pattern: Mu::Node(vec![
+                RuleAction::ToLobe,
+            ),
+            RcxRule::new(
+                Mu::Node(vec![
                     Mu::Sym("news".to_string()),
                     Mu::Sym("paradox".to_string()),
                 ]),
-                action: RuleAction::ToSink,
-            },
+                RuleAction::ToSink,
+            ),
         ],
     }
 }
@@ -166,6 +166,9 @@ fn main() {
                     RuleAction::Rewrite(mu) => {
                         format!("rewrite({})", mu_to_string(mu))
                     }
+                    RuleAction::RewriteTemplate(mu) => {
+                        format!("unify({})", mu_to_string(mu))
+                    }
                 };
                 println!("  {idx}: {pat} -> {tgt}");
             }
@@ -183,7 +186,7 @@ fn main() {
             let mu = match parse_mu(mu_src) {
                 Ok(mu) => mu,
                 Err(e) => {
-                    println!("[why] parse error: {e}");
+                    println!("[why] parse error:\n{}", e.render(mu_src));
                     continue;
                 }
             };
@@ -356,7 +359,7 @@ fn main() {
             let pattern: Mu = match parse_mu(pat_src) {
                 Ok(mu) => mu,
                 Err(e) => {
-                    println!("[learn] pattern parse error: {e}");
+                    println!("[learn] pattern parse error:\n{}", e.render(pat_src));
                     continue;
                 }
             };
@@ -377,7 +380,7 @@ fn main() {
                     }
                 };
 
-                program.rules.push(RcxRule { pattern, action });
+                program.rules.push(RcxRule::new(pattern, action));
                 engine = Engine::new(program.clone());
                 println!("[learn] rule added.");
                 continue;
@@ -394,12 +397,12 @@ fn main() {
                 let rhs_mu: Mu = match parse_mu(rhs_src) {
                     Ok(mu) => mu,
                     Err(e) => {
-                        println!("[learn] rewrite payload parse error: {e}");
+                        println!("[learn] rewrite payload parse error:\n{}", e.render(rhs_src));
                         continue;
                     }
                 };
                 let action = RuleAction::Rewrite(rhs_mu);
-                program.rules.push(RcxRule { pattern, action });
+                program.rules.push(RcxRule::new(pattern, action));
                 engine = Engine::new(program.clone());
                 println!("[learn] rule added.");
                 continue;
@@ -430,7 +433,7 @@ fn main() {
             let seed = match parse_mu(mu_src) {
                 Ok(mu) => mu,
                 Err(e) => {
-                    println!("[orbit] parse error: {e}");
+                    println!("[orbit] parse error:\n{}", e.render(mu_src));
                     continue;
                 }
             };
@@ -463,7 +466,7 @@ fn main() {
             let seed = match parse_mu(mu_src) {
                 Ok(mu) => mu,
                 Err(e) => {
-                    println!("[ω] parse error: {e}");
+                    println!("[ω] parse error:\n{}", e.render(mu_src));
                     continue;
                 }
             };
@@ -533,7 +536,7 @@ fn main() {
                 println!("  sink:  {}", bucket_to_string(&state.sink));
             }
             Err(err) => {
-                println!("parse error: {err}");
+                println!("parse error:\n{}", err.render(line));
             }
         }
     }
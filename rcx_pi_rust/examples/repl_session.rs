@@ -0,0 +1,117 @@
+//! Interactive multi-line RCX-π session.
+//!
+//! Unlike `examples/repl.rs` (single-line, NEWS-world focused), this front
+//! end is built on the `repl` module and supports entering deeply nested
+//! terms across several lines: if a line has unbalanced `[`/`]` (or ends
+//! with a trailing `\`), it keeps reading continuation lines before
+//! attempting `parse_mu`.
+//!
+//! A thin `SyncClient` over `protocol`: with no arguments it drives an
+//! in-process `Session` directly; given `--connect ADDR`, it drives a
+//! `Session` owned by `examples/serve` over the network instead. Either way
+//! the dispatch loop below is identical.
+
+use std::env;
+use std::io::{self, Write};
+
+use rcx_pi_rust::parser::parse_mu;
+use rcx_pi_rust::protocol::{SyncClient, TcpSyncClient};
+use rcx_pi_rust::repl::{append_continuation, needs_continuation, Session};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let connect = args
+        .iter()
+        .position(|a| a == "--connect")
+        .and_then(|i| args.get(i + 1))
+        .cloned();
+
+    let mut client: Box<dyn SyncClient> = match &connect {
+        Some(addr) => match TcpSyncClient::connect(addr.as_str()) {
+            Ok(c) => {
+                println!("RCX-π multi-line session (connected to {addr})");
+                Box::new(c)
+            }
+            Err(e) => {
+                eprintln!("[repl] connect {addr}: {e}");
+                std::process::exit(1);
+            }
+        },
+        None => {
+            println!("RCX-π multi-line session (local)");
+            Box::new(Session::new(Vec::new()))
+        }
+    };
+
+    let mut history: Vec<String> = Vec::new();
+
+    println!(
+        "Commands: :rule <p> -> <action>, :step, :trace, :buckets, :rules, :reset, :json, :save <path>, :load <path>, :q"
+    );
+
+    let stdin = io::stdin();
+    let mut buffer = String::new();
+
+    loop {
+        if buffer.is_empty() {
+            print!("rcx> ");
+        } else {
+            print!("...> ");
+        }
+        io::stdout().flush().ok();
+
+        let mut line = String::new();
+        if stdin.read_line(&mut line).is_err() || line.is_empty() {
+            println!("(input error, exiting)");
+            break;
+        }
+        let line = line.trim_end_matches('\n');
+
+        if buffer.is_empty() {
+            if line.trim() == ":q" || line.trim() == ":quit" {
+                println!("bye.");
+                break;
+            }
+            buffer.push_str(line);
+        } else {
+            append_continuation(&mut buffer, line);
+        }
+
+        if needs_continuation(&buffer) {
+            continue;
+        }
+
+        let entry = buffer.trim().to_string();
+        buffer.clear();
+        if entry.is_empty() {
+            continue;
+        }
+        history.push(entry.clone());
+
+        if entry.starts_with(':') {
+            match client.command(&entry) {
+                Ok(lines) => {
+                    for l in lines {
+                        println!("{l}");
+                    }
+                }
+                Err(e) => println!("[session] {e} (offending input: `{entry}`)"),
+            }
+            continue;
+        }
+
+        match parse_mu(&entry) {
+            Ok(mu) => match client.process_and_wait(mu) {
+                Ok(route) => {
+                    println!("→ route: {:?}", route.kind);
+                    match client.command(":buckets") {
+                        Ok(lines) => lines.iter().for_each(|l| println!("{l}")),
+                        Err(e) => println!("[session] {e}"),
+                    }
+                }
+                Err(e) => println!("[session] {e} (offending input: `{entry}`)"),
+            },
+            Err(e) => println!("[session] {e} (offending input: `{entry}`)"),
+        }
+    }
+}
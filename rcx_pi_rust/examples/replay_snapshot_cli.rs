@@ -3,11 +3,13 @@ use std::fs;
 
 use rcx_pi_rust::{
     engine_json::engine_run_from_state_to_json, parser::parse_mu,
-    snapshot_json::snapshot_from_json, types::Mu,
+    snapshot_json::snapshot_from_json, trace_canon::trace_to_canon_jsonl, types::Mu,
 };
 
 fn usage() -> ! {
-    eprintln!("usage: replay_snapshot_cli <snapshot_json_path> <world_name> [mu ...]");
+    eprintln!(
+        "usage: replay_snapshot_cli <snapshot_json_path> <world_name> [--canon-trace <path>] [mu ...]"
+    );
     eprintln!("example:");
     eprintln!(
         "  replay_snapshot_cli ../docs/fixtures/snapshot_rcx_core_v1.json rcx_core \"[omega,[a,b]]\""
@@ -23,7 +25,18 @@ fn main() {
 
     let snapshot_path = &args[1];
     let world_name = &args[2];
-    let mu_srcs: Vec<String> = args[3..].to_vec();
+
+    let mut canon_trace_path: Option<&str> = None;
+    let mut mu_start = 3;
+    if args.get(3).map(String::as_str) == Some("--canon-trace") {
+        let path = args.get(4).unwrap_or_else(|| {
+            eprintln!("--canon-trace requires a path");
+            usage()
+        });
+        canon_trace_path = Some(path.as_str());
+        mu_start = 5;
+    }
+    let mu_srcs: Vec<String> = args[mu_start..].to_vec();
 
     let json = fs::read_to_string(snapshot_path)
         .unwrap_or_else(|e| panic!("read {}: {}", snapshot_path, e));
@@ -37,5 +50,11 @@ fn main() {
     }
 
     let out = engine_run_from_state_to_json(world_name, &program, &mut state, &inputs);
+
+    if let Some(path) = canon_trace_path {
+        fs::write(path, trace_to_canon_jsonl(&state.trace))
+            .unwrap_or_else(|e| panic!("write {}: {}", path, e));
+    }
+
     print!("{}", out);
 }
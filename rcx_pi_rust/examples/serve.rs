@@ -0,0 +1,85 @@
+//! Headless engine daemon: owns a single `Session` behind a mutex and serves
+//! it to any number of connections over `protocol`'s line-oriented wire
+//! format, so `examples/repl_session` (and any other client) can drive a
+//! shared world locally or over the network through the same `SyncClient`
+//! trait.
+
+use std::env;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+
+use rcx_pi_rust::protocol::handle_request;
+use rcx_pi_rust::repl::Session;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let addr = args.get(1).map(String::as_str).unwrap_or("127.0.0.1:4747");
+
+    let listener = match TcpListener::bind(addr) {
+        Ok(l) => l,
+        Err(e) => {
+            eprintln!("[serve] bind {addr}: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    println!("[serve] listening on {addr}");
+    let session = Arc::new(Mutex::new(Session::new(Vec::new())));
+
+    for incoming in listener.incoming() {
+        let stream = match incoming {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("[serve] accept: {e}");
+                continue;
+            }
+        };
+        let session = Arc::clone(&session);
+        std::thread::spawn(move || handle_connection(stream, session));
+    }
+}
+
+fn handle_connection(stream: std::net::TcpStream, session: Arc<Mutex<Session>>) {
+    let peer = stream
+        .peer_addr()
+        .map(|a| a.to_string())
+        .unwrap_or_else(|_| "?".to_string());
+    println!("[serve] {peer} connected");
+
+    let mut writer = match stream.try_clone() {
+        Ok(w) => w,
+        Err(e) => {
+            eprintln!("[serve] {peer} clone stream: {e}");
+            return;
+        }
+    };
+    let reader = BufReader::new(stream);
+
+    for line in reader.lines() {
+        let line = match line {
+            Ok(l) => l,
+            Err(e) => {
+                eprintln!("[serve] {peer} read: {e}");
+                break;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let reply = {
+            let mut session = match session.lock() {
+                Ok(s) => s,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            handle_request(&mut session, &line)
+        };
+
+        if writeln!(writer, "{reply}").is_err() {
+            break;
+        }
+    }
+
+    println!("[serve] {peer} disconnected");
+}
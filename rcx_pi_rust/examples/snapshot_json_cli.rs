@@ -1,19 +1,47 @@
 use std::env;
+use std::fs;
 
 use rcx_pi_rust::{
     engine::Engine,
     mu_loader::load_mu_file,
     parser::parse_mu,
-    snapshot_json::snapshot_to_json,
+    snapshot_json::{snapshot_canon, snapshot_to_json},
     state::RCXState,
     types::{Mu, RcxProgram},
 };
 
 fn main() {
     let args: Vec<String> = env::args().collect();
+
+    // --check-canon <snapshot.json>: exit nonzero if the file on disk isn't
+    // already in canonical form (same idea as replay_cli's --check-canon,
+    // but for saved snapshot artifacts rather than trace JSONL).
+    if args.len() >= 2 && args[1] == "--check-canon" {
+        if args.len() < 3 {
+            eprintln!("usage: cargo run --example snapshot_json_cli -- --check-canon <path>");
+            std::process::exit(1);
+        }
+        let path = &args[2];
+        let original = fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("[check-canon] read {path}: {e}");
+            std::process::exit(1);
+        });
+        let canon = snapshot_canon(&original).unwrap_or_else(|e| {
+            eprintln!("[check-canon] {e}");
+            std::process::exit(1);
+        });
+        if original != canon {
+            eprintln!("SNAPSHOT_NOT_CANONICAL: {path} differs from its canonical form");
+            std::process::exit(1);
+        }
+        println!("{path} is canonical");
+        return;
+    }
+
     if args.len() < 3 {
         eprintln!("usage:");
         eprintln!("  cargo run --example snapshot_json_cli -- <world> <Mu1> [Mu2 ...]");
+        eprintln!("  cargo run --example snapshot_json_cli -- --check-canon <path>");
         std::process::exit(1);
     }
 
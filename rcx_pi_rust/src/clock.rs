@@ -0,0 +1,154 @@
+//! Pluggable time source for stamping trace events.
+//!
+//! `RCXState::log_event` needs a wall-clock timestamp for each `TraceEvent`,
+//! but reading real time directly would make trace/canonicalization tests
+//! non-deterministic. `Clock` is the seam: production code installs a
+//! `SystemClock`, tests install a `MockClock` with a fixed or
+//! monotonically-incrementing stamp instead.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A source of RFC 3339 (UTC, second precision) timestamps.
+///
+/// `Send + Sync` so a `Box<dyn Clock>` can live inside state that's shared
+/// across threads, e.g. the socket daemon's `Arc<Mutex<Session>>`.
+pub trait Clock: std::fmt::Debug + Send + Sync {
+    /// Returns the current time as an RFC 3339 string, e.g.
+    /// `"2024-01-02T03:04:05Z"`.
+    fn now_rfc3339(&self) -> String;
+
+    /// Clone this clock behind a fresh `Box`, so `RCXState` (which owns a
+    /// `Box<dyn Clock>`) can still derive `Clone`.
+    fn clone_box(&self) -> Box<dyn Clock>;
+}
+
+/// Real wall-clock time, formatted as RFC 3339 UTC with second precision.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_rfc3339(&self) -> String {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        format_unix_rfc3339(secs)
+    }
+
+    fn clone_box(&self) -> Box<dyn Clock> {
+        Box::new(*self)
+    }
+}
+
+/// Deterministic clock for tests: returns a fixed stamp, or advances by one
+/// second on every call when constructed with [`MockClock::ticking`].
+#[derive(Debug)]
+pub struct MockClock {
+    secs: AtomicU64,
+    tick: bool,
+}
+
+impl Clone for MockClock {
+    fn clone(&self) -> Self {
+        Self {
+            secs: AtomicU64::new(self.secs.load(Ordering::Relaxed)),
+            tick: self.tick,
+        }
+    }
+}
+
+impl MockClock {
+    /// A clock that always returns the same timestamp.
+    pub fn fixed(unix_secs: u64) -> Self {
+        Self {
+            secs: AtomicU64::new(unix_secs),
+            tick: false,
+        }
+    }
+
+    /// A clock that starts at `unix_secs` and advances by one second on
+    /// every call to `now_rfc3339`, so successive trace events get distinct,
+    /// reproducible stamps.
+    pub fn ticking(unix_secs: u64) -> Self {
+        Self {
+            secs: AtomicU64::new(unix_secs),
+            tick: true,
+        }
+    }
+}
+
+impl Clock for MockClock {
+    fn now_rfc3339(&self) -> String {
+        let secs = if self.tick {
+            self.secs.fetch_add(1, Ordering::Relaxed)
+        } else {
+            self.secs.load(Ordering::Relaxed)
+        };
+        format_unix_rfc3339(secs)
+    }
+
+    fn clone_box(&self) -> Box<dyn Clock> {
+        Box::new(self.clone())
+    }
+}
+
+/// Format a Unix timestamp (seconds since epoch, UTC) as RFC 3339 without
+/// pulling in a date/time crate. Uses Howard Hinnant's `civil_from_days`
+/// algorithm to turn a day count into a proleptic-Gregorian (y, m, d).
+fn format_unix_rfc3339(unix_secs: u64) -> String {
+    let days = (unix_secs / 86_400) as i64;
+    let secs_of_day = unix_secs % 86_400;
+    let (y, m, d) = civil_from_unix_days(days);
+    let hh = secs_of_day / 3600;
+    let mm = (secs_of_day % 3600) / 60;
+    let ss = secs_of_day % 60;
+    format!("{y:04}-{m:02}-{d:02}T{hh:02}:{mm:02}:{ss:02}Z")
+}
+
+fn civil_from_unix_days(days_since_epoch: i64) -> (i64, u32, u32) {
+    let z = days_since_epoch + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097) as u64; // [0, 146096]
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365; // [0, 399]
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // [0, 365]
+    let mp = (5 * doy + 2) / 153; // [0, 11]
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32; // [1, 31]
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32; // [1, 12]
+    let y = if m <= 2 { y + 1 } else { y };
+    (y, m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mock_clock_fixed_returns_same_stamp() {
+        let c = MockClock::fixed(0);
+        assert_eq!(c.now_rfc3339(), "1970-01-01T00:00:00Z");
+        assert_eq!(c.now_rfc3339(), "1970-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn mock_clock_ticking_advances_by_one_second() {
+        let c = MockClock::ticking(0);
+        assert_eq!(c.now_rfc3339(), "1970-01-01T00:00:00Z");
+        assert_eq!(c.now_rfc3339(), "1970-01-01T00:00:01Z");
+        assert_eq!(c.now_rfc3339(), "1970-01-01T00:00:02Z");
+    }
+
+    #[test]
+    fn known_unix_timestamp_formats_correctly() {
+        // 2024-01-02T03:04:05Z
+        assert_eq!(format_unix_rfc3339(1_704_164_645), "2024-01-02T03:04:05Z");
+    }
+
+    #[test]
+    fn system_clock_produces_well_formed_stamp() {
+        let stamp = SystemClock.now_rfc3339();
+        assert_eq!(stamp.len(), "2024-01-02T03:04:05Z".len());
+        assert!(stamp.ends_with('Z'));
+    }
+}
@@ -0,0 +1,333 @@
+//! Local confluence checking for an `RcxProgram`'s `Rewrite` rules via
+//! critical-pair analysis.
+//!
+//! For every ordered pair of rules `(l1 -> r1, l2 -> r2)` and every
+//! non-variable position `p` in `l1`, if `l2` unifies with `l1|p` under a
+//! substitution `σ`, then the common ancestor `l1σ` rewrites two different
+//! ways at once: wholesale via rule 1 (giving `r1σ`), or just at `p` via
+//! rule 2 (giving `l1σ` with `p` replaced by `r2σ`). That pair of divergent
+//! results is a *critical pair* - the rule set is locally confluent at this
+//! overlap iff both sides reach the same normal form under
+//! `congruence::normalize`. This lets authors of a `Rewrite` rule set catch
+//! order-dependent/ambiguous overlaps before running them.
+//!
+//! Rule 2's pattern variables are renamed apart from rule 1's before
+//! unifying, since `matching::is_var` treats any single lowercase letter as
+//! a variable and the two rules may reuse the same letter for unrelated
+//! bindings.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::congruence::{self, Position, Strategy};
+use crate::matching::{is_var, Env};
+use crate::types::{Mu, RcxProgram, RuleAction};
+
+/// A critical pair: the two terms a common ancestor diverges to when
+/// `rule_i1` fires at the root and `rule_i2` fires at `position` instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CriticalPair {
+    pub rule_i1: usize,
+    pub rule_i2: usize,
+    pub position: Position,
+    /// `rule_i1`'s right-hand side under the unifying substitution.
+    pub left: Mu,
+    /// The common ancestor with `position` rewritten by `rule_i2` instead.
+    pub right: Mu,
+}
+
+/// A [`CriticalPair`] together with whether its two sides are joinable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfluenceCheck {
+    pub pair: CriticalPair,
+    /// `true` if `left` and `right` normalize to the same term within the
+    /// step budget `check_confluence` was given.
+    pub joinable: bool,
+}
+
+fn vars_in(term: &Mu, out: &mut HashSet<String>) {
+    match term {
+        Mu::Sym(name) if is_var(name) => {
+            out.insert(name.clone());
+        }
+        Mu::Sym(_) => {}
+        Mu::Node(children) => children.iter().for_each(|c| vars_in(c, out)),
+    }
+}
+
+fn apply_rename(term: &Mu, rename: &HashMap<String, String>) -> Mu {
+    match term {
+        Mu::Sym(name) if is_var(name) => {
+            Mu::Sym(rename.get(name).cloned().unwrap_or_else(|| name.clone()))
+        }
+        Mu::Sym(name) => Mu::Sym(name.clone()),
+        Mu::Node(children) => Mu::Node(children.iter().map(|c| apply_rename(c, rename)).collect()),
+    }
+}
+
+/// Rename `pattern`/`template`'s variables to single lowercase letters not
+/// in `avoid`, consistently, so unifying them against another rule's
+/// pattern can't confuse the two rules' unrelated bindings for the same
+/// name. Variables left over once the (26-letter) namespace is exhausted
+/// are passed through unrenamed - rare enough for this toy language's
+/// rules not to be worth a bigger variable space.
+fn rename_apart(pattern: &Mu, template: &Mu, avoid: &HashSet<String>) -> (Mu, Mu) {
+    let mut used = avoid.clone();
+    let mut to_rename = HashSet::new();
+    vars_in(pattern, &mut to_rename);
+
+    let mut letters = 'a'..='z';
+    let mut rename: HashMap<String, String> = HashMap::new();
+    for var in to_rename {
+        let fresh = loop {
+            match letters.next() {
+                Some(c) => {
+                    let name = c.to_string();
+                    if !used.contains(&name) {
+                        break Some(name);
+                    }
+                }
+                None => break None,
+            }
+        };
+        if let Some(name) = fresh {
+            used.insert(name.clone());
+            rename.insert(var, name);
+        }
+    }
+
+    (apply_rename(pattern, &rename), apply_rename(template, &rename))
+}
+
+/// Follow `term` through `subst` while it's a bound variable.
+fn walk(term: &Mu, subst: &Env) -> Mu {
+    if let Mu::Sym(name) = term {
+        if is_var(name) {
+            if let Some(bound) = subst.get(name) {
+                return walk(bound, subst);
+            }
+        }
+    }
+    term.clone()
+}
+
+fn occurs(var: &str, term: &Mu, subst: &Env) -> bool {
+    match walk(term, subst) {
+        Mu::Sym(name) => name == var,
+        Mu::Node(children) => children.iter().any(|c| occurs(var, c, subst)),
+    }
+}
+
+/// Occurs-checked unification of two patterns, both of which may contain
+/// `is_var` variables (unlike `matching::match_pattern`, which only allows
+/// variables on one side). Extends `subst` in place; returns `false`
+/// (leaving `subst` in an unspecified partial state) on failure.
+fn unify(a: &Mu, b: &Mu, subst: &mut Env) -> bool {
+    let a = walk(a, subst);
+    let b = walk(b, subst);
+
+    match (&a, &b) {
+        (Mu::Sym(x), Mu::Sym(y)) if is_var(x) && is_var(y) && x == y => true,
+        (Mu::Sym(x), _) if is_var(x) => {
+            if occurs(x, &b, subst) {
+                false
+            } else {
+                subst.insert(x.clone(), b);
+                true
+            }
+        }
+        (_, Mu::Sym(y)) if is_var(y) => {
+            if occurs(y, &a, subst) {
+                false
+            } else {
+                subst.insert(y.clone(), a);
+                true
+            }
+        }
+        (Mu::Sym(x), Mu::Sym(y)) => x == y,
+        (Mu::Node(xs), Mu::Node(ys)) => {
+            xs.len() == ys.len() && xs.iter().zip(ys.iter()).all(|(x, y)| unify(x, y, subst))
+        }
+        _ => false,
+    }
+}
+
+/// Fully resolve every variable in `term` through `subst`, recursively -
+/// unlike `matching::substitute_template`, which only substitutes one
+/// level deep and trusts there's no var-to-var chain left to follow.
+fn resolve(term: &Mu, subst: &Env) -> Mu {
+    match term {
+        Mu::Sym(name) if is_var(name) => match subst.get(name) {
+            Some(bound) => resolve(bound, subst),
+            None => term.clone(),
+        },
+        Mu::Sym(_) => term.clone(),
+        Mu::Node(children) => Mu::Node(children.iter().map(|c| resolve(c, subst)).collect()),
+    }
+}
+
+fn all_positions(term: &Mu, prefix: &mut Position, out: &mut Vec<Position>) {
+    out.push(prefix.clone());
+    if let Mu::Node(children) = term {
+        for (i, child) in children.iter().enumerate() {
+            prefix.push(i);
+            all_positions(child, prefix, out);
+            prefix.pop();
+        }
+    }
+}
+
+/// Every critical pair among `program`'s `Rewrite` rules.
+pub fn critical_pairs(program: &RcxProgram) -> Vec<CriticalPair> {
+    let rules: Vec<(usize, &Mu, &Mu)> = program
+        .rules
+        .iter()
+        .enumerate()
+        .filter_map(|(i, rule)| match &rule.action {
+            RuleAction::Rewrite(template) => Some((i, &rule.pattern, template)),
+            _ => None,
+        })
+        .collect();
+
+    let mut out = Vec::new();
+
+    for &(rule_i1, l1, r1) in &rules {
+        let mut l1_vars = HashSet::new();
+        vars_in(l1, &mut l1_vars);
+
+        let mut positions = Vec::new();
+        all_positions(l1, &mut Position::new(), &mut positions);
+
+        for &(rule_i2, l2, r2) in &rules {
+            let (l2, r2) = rename_apart(l2, r2, &l1_vars);
+
+            for position in &positions {
+                if position.is_empty() && rule_i1 == rule_i2 {
+                    continue; // a rule trivially overlaps with itself at its own root
+                }
+
+                let Some(subterm) = congruence::get(l1, position) else {
+                    continue;
+                };
+                if matches!(subterm, Mu::Sym(name) if is_var(name)) {
+                    continue; // only non-variable positions count as overlaps
+                }
+
+                let mut subst = Env::new();
+                if !unify(subterm, &l2, &mut subst) {
+                    continue;
+                }
+
+                let ancestor = resolve(l1, &subst);
+                let left = resolve(r1, &subst);
+                let right = congruence::replace_at(&ancestor, position, resolve(&r2, &subst));
+
+                out.push(CriticalPair {
+                    rule_i1,
+                    rule_i2,
+                    position: position.clone(),
+                    left,
+                    right,
+                });
+            }
+        }
+    }
+
+    out
+}
+
+/// Check every critical pair in `program` for local confluence: reduce
+/// both diverging terms under `strategy`, to a normal form or `max_steps`
+/// steps (whichever comes first), and record whether they land on the
+/// same term.
+pub fn check_confluence(
+    program: &RcxProgram,
+    strategy: Strategy,
+    max_steps: usize,
+) -> Vec<ConfluenceCheck> {
+    critical_pairs(program)
+        .into_iter()
+        .map(|pair| {
+            let left_nf = congruence::normalize(program, pair.left.clone(), strategy, max_steps);
+            let right_nf = congruence::normalize(program, pair.right.clone(), strategy, max_steps);
+            let joinable = left_nf.last() == right_nf.last();
+            ConfluenceCheck { pair, joinable }
+        })
+        .collect()
+}
+
+/// The non-joinable critical pairs from `check_confluence` - concrete
+/// evidence of a potential non-confluence, each naming the two rules
+/// involved and the two terms they diverge to.
+pub fn non_joinable_critical_pairs(
+    program: &RcxProgram,
+    strategy: Strategy,
+    max_steps: usize,
+) -> Vec<CriticalPair> {
+    check_confluence(program, strategy, max_steps)
+        .into_iter()
+        .filter(|check| !check.joinable)
+        .map(|check| check.pair)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::RcxRule;
+
+    fn sym(s: &str) -> Mu {
+        Mu::Sym(s.to_string())
+    }
+
+    fn node(children: Vec<Mu>) -> Mu {
+        Mu::Node(children)
+    }
+
+    #[test]
+    fn single_rule_has_no_overlaps() {
+        let program = RcxProgram {
+            rules: vec![RcxRule::new(
+                node(vec![sym("x"), sym("x")]),
+                RuleAction::Rewrite(sym("STABLE")),
+            )],
+        };
+
+        let pairs = critical_pairs(&program);
+        assert!(pairs.is_empty(), "a single rule can't overlap with anything else");
+    }
+
+    #[test]
+    fn detects_a_genuine_non_confluence() {
+        // F(x) -> A, F(B) -> C: these overlap at the root (unifying x
+        // with B), and diverge to A vs C with no rule to reconcile them.
+        let program = RcxProgram {
+            rules: vec![
+                RcxRule::new(
+                    node(vec![sym("F"), sym("x")]),
+                    RuleAction::Rewrite(sym("A")),
+                ),
+                RcxRule::new(
+                    node(vec![sym("F"), sym("B")]),
+                    RuleAction::Rewrite(sym("C")),
+                ),
+            ],
+        };
+
+        let bad = non_joinable_critical_pairs(&program, Strategy::OutermostLeftmost, 10);
+        assert_eq!(bad.len(), 2); // (rule 0 over rule 1) and (rule 1 over rule 0)
+        assert!(bad.iter().any(|p| p.rule_i1 == 0 && p.rule_i2 == 1 && p.left == sym("A") && p.right == sym("C")));
+        assert!(bad.iter().any(|p| p.rule_i1 == 1 && p.rule_i2 == 0 && p.left == sym("C") && p.right == sym("A")));
+    }
+
+    #[test]
+    fn non_variable_position_required_for_an_overlap() {
+        // x -> A: the pattern is a bare variable, so it has no non-variable
+        // position for anything else to overlap with (besides trivially
+        // unifying at the root, which only counts against a *different*
+        // rule - here there's only the one).
+        let program = RcxProgram {
+            rules: vec![RcxRule::new(sym("x"), RuleAction::Rewrite(sym("A")))],
+        };
+        assert!(critical_pairs(&program).is_empty());
+    }
+}
@@ -0,0 +1,169 @@
+//! Differential conformance harness for `trace_canon`.
+//!
+//! `trace_canon` documents itself as a bit-for-bit MIRROR of Python's
+//! `rcx_pi/trace_canon.py`, but that claim was previously only as good as
+//! whoever last eyeballed the two implementations side by side. This module
+//! pins it down: each `ConformanceCase` carries raw input JSONL alongside
+//! the exact output (or error) the Python reference is known to produce,
+//! and `run_cases` reports every mismatch instead of stopping at the first.
+
+use crate::trace_canon::{canon_jsonl, read_jsonl};
+
+/// What a `ConformanceCase` expects `canon_jsonl(read_jsonl(input))` to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expected {
+    /// Canonicalization must succeed and produce exactly this JSONL text.
+    Output(&'static str),
+    /// Canonicalization must fail with an error containing this substring -
+    /// a negative case pinning one of the frozen invariants (non-empty
+    /// `type`, index contiguity, `meta` must be an object, ...).
+    ErrorContains(&'static str),
+}
+
+/// One golden vector: a named input/expected-output pair, modeled on the
+/// paired fixture files a differential test suite would load from disk.
+#[derive(Debug, Clone, Copy)]
+pub struct ConformanceCase {
+    pub name: &'static str,
+    pub input: &'static str,
+    pub expected: Expected,
+}
+
+/// The outcome of running a single `ConformanceCase`.
+#[derive(Debug, Clone)]
+pub struct CaseResult {
+    pub name: &'static str,
+    pub passed: bool,
+    /// On failure, what was expected vs. what `trace_canon` actually did.
+    pub detail: String,
+}
+
+/// The result of running a whole suite of `ConformanceCase`s.
+#[derive(Debug, Clone)]
+pub struct ConformanceReport {
+    pub results: Vec<CaseResult>,
+}
+
+impl ConformanceReport {
+    pub fn all_passed(&self) -> bool {
+        self.results.iter().all(|r| r.passed)
+    }
+
+    pub fn failures(&self) -> Vec<&CaseResult> {
+        self.results.iter().filter(|r| !r.passed).collect()
+    }
+}
+
+/// Run one case and report whether `trace_canon` matched the golden vector.
+pub fn run_case(case: &ConformanceCase) -> CaseResult {
+    let outcome = read_jsonl(case.input).and_then(|events| canon_jsonl(&events));
+
+    let (passed, detail) = match (case.expected, outcome) {
+        (Expected::Output(expected), Ok(actual)) if actual == expected => (true, "ok".to_string()),
+        (Expected::Output(expected), Ok(actual)) => (
+            false,
+            format!("expected output:\n{expected}\nactual output:\n{actual}"),
+        ),
+        (Expected::Output(expected), Err(e)) => (
+            false,
+            format!("expected output:\n{expected}\ngot error instead: {e}"),
+        ),
+        (Expected::ErrorContains(substr), Err(e)) if e.to_string().contains(substr) => {
+            (true, "ok".to_string())
+        }
+        (Expected::ErrorContains(substr), Err(e)) => (
+            false,
+            format!("expected error containing `{substr}`, got error: {e}"),
+        ),
+        (Expected::ErrorContains(substr), Ok(actual)) => (
+            false,
+            format!("expected error containing `{substr}`, got success:\n{actual}"),
+        ),
+    };
+
+    CaseResult {
+        name: case.name,
+        passed,
+        detail,
+    }
+}
+
+/// Run every case in `cases`, collecting a result for each one rather than
+/// stopping at the first mismatch.
+pub fn run_cases(cases: &[ConformanceCase]) -> ConformanceReport {
+    ConformanceReport {
+        results: cases.iter().map(run_case).collect(),
+    }
+}
+
+/// Golden vectors pinning `trace_canon`'s frozen v1 semantics.
+pub const GOLDEN_VECTORS: &[ConformanceCase] = &[
+    ConformanceCase {
+        name: "reorders_keys_and_sorts_meta",
+        input: "{\"type\":\"trace.start\",\"i\":0,\"v\":1}\n{\"v\":1,\"i\":1,\"type\":\"trace.step\",\"meta\":{\"b\":2,\"a\":1}}\n",
+        expected: Expected::Output(
+            "{\"v\":1,\"type\":\"trace.start\",\"i\":0}\n{\"v\":1,\"type\":\"trace.step\",\"i\":1,\"meta\":{\"a\":1,\"b\":2}}\n",
+        ),
+    },
+    ConformanceCase {
+        name: "blank_lines_are_skipped",
+        input: "{\"v\":1,\"type\":\"trace.start\",\"i\":0}\n\n   \n{\"v\":1,\"type\":\"trace.end\",\"i\":1}\n",
+        expected: Expected::Output(
+            "{\"v\":1,\"type\":\"trace.start\",\"i\":0}\n{\"v\":1,\"type\":\"trace.end\",\"i\":1}\n",
+        ),
+    },
+    ConformanceCase {
+        name: "empty_type_is_rejected",
+        input: "{\"v\":1,\"type\":\"\",\"i\":0}\n",
+        expected: Expected::ErrorContains("must be a non-empty string"),
+    },
+    ConformanceCase {
+        name: "meta_must_be_an_object",
+        input: "{\"v\":1,\"type\":\"trace.start\",\"i\":0,\"meta\":\"nope\"}\n",
+        expected: Expected::ErrorContains("must be an object"),
+    },
+    ConformanceCase {
+        name: "index_gap_breaks_contiguity",
+        input: "{\"v\":1,\"type\":\"trace.start\",\"i\":0}\n{\"v\":1,\"type\":\"trace.step\",\"i\":2}\n",
+        expected: Expected::ErrorContains("contiguous"),
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn golden_vectors_all_pass() {
+        let report = run_cases(GOLDEN_VECTORS);
+        for failure in report.failures() {
+            eprintln!("FAIL {}: {}", failure.name, failure.detail);
+        }
+        assert!(report.all_passed());
+    }
+
+    #[test]
+    fn run_case_reports_failure_detail_on_mismatch() {
+        let case = ConformanceCase {
+            name: "deliberately_wrong",
+            input: "{\"v\":1,\"type\":\"trace.start\",\"i\":0}\n",
+            expected: Expected::Output("{\"v\":1,\"type\":\"trace.end\",\"i\":0}\n"),
+        };
+        let result = run_case(&case);
+        assert!(!result.passed);
+        assert!(result.detail.contains("trace.end"));
+        assert!(result.detail.contains("trace.start"));
+    }
+
+    #[test]
+    fn negative_case_fails_if_canonicalization_unexpectedly_succeeds() {
+        let case = ConformanceCase {
+            name: "expected_error_but_got_success",
+            input: "{\"v\":1,\"type\":\"trace.start\",\"i\":0}\n",
+            expected: Expected::ErrorContains("must be a non-empty string"),
+        };
+        let result = run_case(&case);
+        assert!(!result.passed);
+        assert!(result.detail.contains("got success"));
+    }
+}
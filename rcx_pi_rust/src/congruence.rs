@@ -0,0 +1,289 @@
+//! Subterm (congruence) rewriting: unlike `orbit::step`, which only tries a
+//! rule's pattern against the *whole* term, this matches every rule against
+//! every position in the term - root and all descendants - so a rule like
+//! `X -> STABLE` can fire inside `[a, X, b]`, not just when the whole input
+//! is `X`.
+//!
+//! Positions are `Vec<usize>` child-index paths (`[]` is the root, `[1]` its
+//! second child, `[1, 0]` that child's first child, ...). Two reduction
+//! strategies pick which redex a single step fires when more than one term
+//! in the tree matches: outermost-leftmost tries shallow positions before
+//! deep ones, innermost-leftmost normalizes children before their parent.
+//!
+//! Because a single `normalize` run can revisit the same rule at several
+//! positions across several steps, substitution here is hygienic by
+//! default (`substitute_template_hygienic`): `step`/`normalize` thread a
+//! `FreshVars` counter through the whole reduction sequence so a value
+//! substituted in at one step can never be captured by a variable a later
+//! step's template still has open.
+
+use crate::matching::{match_pattern, substitute_template_hygienic, Env, FreshVars};
+use crate::types::{Mu, RcxProgram, RcxRule, RuleAction};
+
+/// A child-index path identifying a subterm of some root `Mu`.
+pub type Position = Vec<usize>;
+
+/// Which matching position `step` picks when a term has more than one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Shallowest, leftmost matching position first.
+    OutermostLeftmost,
+    /// Deepest, leftmost matching position first - children normalize
+    /// before their parent.
+    InnermostLeftmost,
+}
+
+/// A redex: a position in some term together with the first rule (in
+/// program order) whose pattern matches the subterm found there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redex {
+    pub position: Position,
+    pub rule_i: usize,
+}
+
+/// The subterm at `position`, or `None` if the path runs past a `Sym` or
+/// off the end of a `Node`'s children.
+pub fn get<'a>(term: &'a Mu, position: &[usize]) -> Option<&'a Mu> {
+    match position.split_first() {
+        None => Some(term),
+        Some((&i, rest)) => match term {
+            Mu::Node(children) => children.get(i).and_then(|child| get(child, rest)),
+            Mu::Sym(_) => None,
+        },
+    }
+}
+
+/// `term` with the subtree at `position` replaced by `replacement`.
+pub(crate) fn replace_at(term: &Mu, position: &[usize], replacement: Mu) -> Mu {
+    match position.split_first() {
+        None => replacement,
+        Some((&i, rest)) => match term {
+            Mu::Node(children) => {
+                let mut children = children.clone();
+                if let Some(child) = children.get_mut(i) {
+                    *child = replace_at(child, rest, replacement);
+                }
+                Mu::Node(children)
+            }
+            Mu::Sym(_) => term.clone(),
+        },
+    }
+}
+
+/// The first `Rewrite` rule (in program order) whose pattern matches
+/// `term`, if any.
+fn first_matching_rule(program: &RcxProgram, term: &Mu) -> Option<usize> {
+    for (rule_i, RcxRule { pattern, action, .. }) in program.rules.iter().enumerate() {
+        if let RuleAction::Rewrite(_) = action {
+            let mut env: Env = Env::new();
+            if match_pattern(pattern, term, &mut env) {
+                return Some(rule_i);
+            }
+        }
+    }
+    None
+}
+
+/// Collect redexes root-first, then children left-to-right - shallow
+/// positions come before the deep ones nested under them.
+fn collect_outermost(program: &RcxProgram, term: &Mu, prefix: &mut Position, out: &mut Vec<Redex>) {
+    if let Some(rule_i) = first_matching_rule(program, term) {
+        out.push(Redex { position: prefix.clone(), rule_i });
+    }
+    if let Mu::Node(children) = term {
+        for (i, child) in children.iter().enumerate() {
+            prefix.push(i);
+            collect_outermost(program, child, prefix, out);
+            prefix.pop();
+        }
+    }
+}
+
+/// Collect redexes children-first (left-to-right), then root - deep
+/// positions come before the parents that contain them.
+fn collect_innermost(program: &RcxProgram, term: &Mu, prefix: &mut Position, out: &mut Vec<Redex>) {
+    if let Mu::Node(children) = term {
+        for (i, child) in children.iter().enumerate() {
+            prefix.push(i);
+            collect_innermost(program, child, prefix, out);
+            prefix.pop();
+        }
+    }
+    if let Some(rule_i) = first_matching_rule(program, term) {
+        out.push(Redex { position: prefix.clone(), rule_i });
+    }
+}
+
+/// Every redex in `term`, ordered per `strategy`.
+pub fn redexes(program: &RcxProgram, term: &Mu, strategy: Strategy) -> Vec<Redex> {
+    let mut out = Vec::new();
+    let mut prefix = Position::new();
+    match strategy {
+        Strategy::OutermostLeftmost => collect_outermost(program, term, &mut prefix, &mut out),
+        Strategy::InnermostLeftmost => collect_innermost(program, term, &mut prefix, &mut out),
+    }
+    out
+}
+
+/// Rewrite the subterm at `position` per `rule_i`, and splice the result
+/// back into `term`. Panics if `position`/`rule_i` don't describe an
+/// actual redex of `term` - callers are expected to pass one straight out
+/// of `redexes`/`step`.
+fn rewrite_at(program: &RcxProgram, term: &Mu, position: &Position, rule_i: usize, fresh: &mut FreshVars) -> Mu {
+    let subterm = get(term, position).expect("redex position must exist in term");
+    let template = match &program.rules[rule_i].action {
+        RuleAction::Rewrite(template) => template,
+        _ => panic!("redex rule index must name a Rewrite rule"),
+    };
+
+    let mut env: Env = Env::new();
+    let matched = match_pattern(&program.rules[rule_i].pattern, subterm, &mut env);
+    assert!(matched, "redex rule must still match its recorded position");
+
+    replace_at(term, position, substitute_template_hygienic(template, &env, fresh))
+}
+
+/// One congruence-rewrite step: find the first redex `strategy` picks and
+/// rewrite it, or `None` if `term` has no redex at all (it's in normal
+/// form). `fresh` mints the variable names the substitution uses to keep
+/// this step hygienic - callers driving a whole reduction sequence (e.g.
+/// `normalize`) should reuse the same counter across every step.
+pub fn step(program: &RcxProgram, term: &Mu, strategy: Strategy, fresh: &mut FreshVars) -> Option<Mu> {
+    let redex = redexes(program, term, strategy).into_iter().next()?;
+    Some(rewrite_at(program, term, &redex.position, redex.rule_i, fresh))
+}
+
+/// Repeatedly apply `step` under `strategy`, starting from `term`, until no
+/// redex remains or `max_steps` is hit. Returns the full reduction
+/// sequence, the seed term included as the first element. A single
+/// `FreshVars` counter is threaded across every step in the sequence, so a
+/// later step can't accidentally reuse a fresh name an earlier step already
+/// minted.
+pub fn normalize(program: &RcxProgram, term: Mu, strategy: Strategy, max_steps: usize) -> Vec<Mu> {
+    let mut seq = vec![term];
+    let mut fresh = FreshVars::new();
+
+    for _ in 0..max_steps {
+        let current = seq.last().expect("seq always has at least the seed");
+        match step(program, current, strategy, &mut fresh) {
+            Some(next) => seq.push(next),
+            None => break,
+        }
+    }
+
+    seq
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sym(s: &str) -> Mu {
+        Mu::Sym(s.to_string())
+    }
+
+    fn node(children: Vec<Mu>) -> Mu {
+        Mu::Node(children)
+    }
+
+    #[test]
+    fn fires_inside_a_surrounding_node() {
+        // X -> STABLE; seed is [a, X, b] - the whole-term matcher in
+        // orbit.rs would never touch this, only congruence rewriting does.
+        let program = RcxProgram {
+            rules: vec![RcxRule::new(sym("X"), RuleAction::Rewrite(sym("STABLE")))],
+        };
+        let seed = node(vec![sym("a"), sym("X"), sym("b")]);
+
+        let redexes = redexes(&program, &seed, Strategy::OutermostLeftmost);
+        assert_eq!(redexes, vec![Redex { position: vec![1], rule_i: 0 }]);
+
+        let mut fresh = FreshVars::new();
+        let next = step(&program, &seed, Strategy::OutermostLeftmost, &mut fresh).unwrap();
+        assert_eq!(next, node(vec![sym("a"), sym("STABLE"), sym("b")]));
+    }
+
+    #[test]
+    fn outermost_prefers_the_shallower_redex() {
+        // [X, X] -> STABLE, and X -> Y: the root itself matches the first
+        // rule, while the leaves match the second - outermost takes root.
+        let program = RcxProgram {
+            rules: vec![
+                RcxRule::new(
+                    node(vec![sym("X"), sym("X")]),
+                    RuleAction::Rewrite(sym("STABLE")),
+                ),
+                RcxRule::new(sym("X"), RuleAction::Rewrite(sym("Y"))),
+            ],
+        };
+        let seed = node(vec![sym("X"), sym("X")]);
+
+        let mut fresh = FreshVars::new();
+        let next = step(&program, &seed, Strategy::OutermostLeftmost, &mut fresh).unwrap();
+        assert_eq!(next, sym("STABLE"));
+    }
+
+    #[test]
+    fn innermost_normalizes_children_first() {
+        let program = RcxProgram {
+            rules: vec![
+                RcxRule::new(
+                    node(vec![sym("X"), sym("X")]),
+                    RuleAction::Rewrite(sym("STABLE")),
+                ),
+                RcxRule::new(sym("X"), RuleAction::Rewrite(sym("Y"))),
+            ],
+        };
+        let seed = node(vec![sym("X"), sym("X")]);
+
+        // Innermost fires on a leaf `X` before the `[X, X]` root redex.
+        let mut fresh = FreshVars::new();
+        let next = step(&program, &seed, Strategy::InnermostLeftmost, &mut fresh).unwrap();
+        assert_eq!(next, node(vec![sym("Y"), sym("X")]));
+    }
+
+    #[test]
+    fn normalize_runs_to_a_fixpoint() {
+        let program = RcxProgram {
+            rules: vec![RcxRule::new(sym("X"), RuleAction::Rewrite(sym("Y")))],
+        };
+        let seed = node(vec![sym("X"), sym("X"), sym("a")]);
+
+        let seq = normalize(&program, seed, Strategy::InnermostLeftmost, 10);
+        assert_eq!(seq.last().unwrap(), &node(vec![sym("Y"), sym("Y"), sym("a")]));
+        assert_eq!(seq.len(), 3); // seed + two leftmost X->Y steps
+    }
+
+    #[test]
+    fn normal_form_has_no_redex() {
+        let program = RcxProgram {
+            rules: vec![RcxRule::new(sym("X"), RuleAction::Rewrite(sym("Y")))],
+        };
+        let seed = sym("Z");
+        assert!(redexes(&program, &seed, Strategy::OutermostLeftmost).is_empty());
+        let mut fresh = FreshVars::new();
+        assert_eq!(step(&program, &seed, Strategy::OutermostLeftmost, &mut fresh), None);
+    }
+
+    #[test]
+    fn a_bound_value_cannot_capture_the_templates_own_leftover_variable() {
+        // [F, x] -> [x, y]: binds x, leaves y open. Seed carries a bound
+        // value that itself contains a free "y" - a non-hygienic
+        // substitution would leave that "y" indistinguishable from the
+        // template's own open "y" in the result.
+        let program = RcxProgram {
+            rules: vec![RcxRule::new(
+                node(vec![sym("F"), sym("x")]),
+                RuleAction::Rewrite(node(vec![sym("x"), sym("y")])),
+            )],
+        };
+        let seed = node(vec![sym("F"), node(vec![sym("y")])]);
+
+        let next = step(&program, &seed, Strategy::OutermostLeftmost, &mut FreshVars::new()).unwrap();
+        let Mu::Node(children) = &next else {
+            panic!("expected a node");
+        };
+        assert_eq!(children[0], node(vec![sym("y")])); // the carried-in value, untouched
+        assert_ne!(children[1], sym("y")); // the template's own variable, renamed apart
+    }
+}
@@ -1,37 +1,14 @@
+use std::collections::HashMap;
+
+use crate::json_mu::json_to_mu;
+use crate::json_value::JsonValue;
+use crate::pattern::mu_match_bind;
+use crate::pattern::substitute;
 use crate::state::RCXState;
 use crate::trace::RouteKind;
 use crate::traits::{Classification, classify};
 use crate::types::{Mu, RcxProgram, RuleAction};
-
-/// Simple pattern matcher with `_` as a wildcard symbol.
-///
-/// Rules:
-///   - `Sym("_")` matches any Mu (symbol or node).
-///   - `Sym("foo")` matches only `Sym("foo")`.
-///   - `Node([...])` matches `Node([...])` of the same length, elementwise.
-fn pattern_matches(pattern: &Mu, value: &Mu) -> bool {
-    match (pattern, value) {
-        // `_` wildcard: matches anything
-        (Mu::Sym(p), _) if p == "_" => true,
-
-        // Symbol must match exactly
-        (Mu::Sym(p), Mu::Sym(v)) => p == v,
-
-        // Node: same length, all children must match
-        (Mu::Node(p_children), Mu::Node(v_children)) => {
-            if p_children.len() != v_children.len() {
-                return false;
-            }
-            p_children
-                .iter()
-                .zip(v_children.iter())
-                .all(|(p_child, v_child)| pattern_matches(p_child, v_child))
-        }
-
-        // Anything else does not match
-        _ => false,
-    }
-}
+use crate::unify::{apply_subst, unify};
 
 /// RCX-π Engine: wraps a program + structural classifier
 /// and routes each Mu into r_a / lobes / sink,
@@ -48,6 +25,7 @@ impl Engine {
     /// Process a single input Mu:
     /// 1) Try explicit program rules (including Rewrite).
     /// 2) If no rule matches, fall back to structural classification.
+    ///
     /// Returns the final route (Ra / Lobe / Sink / Structural).
     pub fn process_input(&mut self, state: &mut RCXState, input: Mu) -> Option<RouteKind> {
         // 1) Try explicit program rules first.
@@ -60,16 +38,85 @@ impl Engine {
         Some(route)
     }
 
+    /// Parse `input` as a stream of NDJSON / concatenated-JSON documents
+    /// (`JsonValue::parse_stream`), convert each one to a `Mu` term
+    /// (`json_to_mu`), and run it through `process_input` in turn.
+    ///
+    /// Stops at the first malformed document and returns its error
+    /// (byte-offset included) rather than skipping it, so a caller driving
+    /// a file of JSON events straight through the pipeline notices a
+    /// truncated or corrupt line instead of silently losing it.
+    pub fn process_json_stream(
+        &mut self,
+        state: &mut RCXState,
+        input: &str,
+    ) -> Result<Vec<Option<RouteKind>>, String> {
+        let mut routes = Vec::new();
+        for doc in JsonValue::parse_stream(input) {
+            let value = doc?;
+            let mu = json_to_mu(&value);
+            routes.push(self.process_input(state, mu));
+        }
+        Ok(routes)
+    }
+
     /// Apply program rules (ToRa / ToLobe / ToSink / Rewrite).
     /// If a rule fires, we log an event and return the resulting route.
     /// If nothing matches, return None and let the caller fall back.
+    ///
+    /// This already does schema-style, variable-binding rewriting rather
+    /// than ground-only matching: `Rewrite` binds `?x`-style captures via
+    /// `mu_match_bind`/`substitute`, and `RewriteTemplate` binds `unify`'s
+    /// single-uppercase-letter variables via `unify`/`apply_subst`.
+    ///
+    /// Status re: "wire `matching::match_pattern` into `apply_program_rules`
+    /// so `[pair, x, y]` binds via its single-lowercase-letter `Env`,
+    /// replacing ground-only equality" - not done, and not planned here.
+    /// `match_pattern`/`Env` (used by `orbit.rs`) is a third, independent
+    /// variable convention; swapping it in under `apply_program_rules` would
+    /// re-interpret every `.mu`/DSL rewrite rule already written in `?x`
+    /// form (their lowercase symbols would suddenly bind instead of staying
+    /// literal) and break them rather than add a capability. The capability
+    /// the request wanted - rule patterns that bind and carry a subterm into
+    /// the rewritten output - is already delivered above, just via
+    /// `mu_match_bind`'s `?x` convention instead of `match_pattern`'s.
     fn apply_program_rules(&mut self, state: &mut RCXState, input: &Mu) -> Option<RouteKind> {
         for rule in &self.program.rules {
-            // IMPORTANT: use pattern_matches instead of equality
-            if !pattern_matches(&rule.pattern, input) {
+            // `RewriteTemplate` uses `unify`'s own variable convention
+            // (a single uppercase letter, optionally with trailing digits),
+            // so it bypasses `mu_match_bind` entirely rather than sharing
+            // its `?x`-capture gate below.
+            if let RuleAction::RewriteTemplate(template) = &rule.action {
+                let Some(subst) = unify(&rule.pattern, input) else {
+                    continue;
+                };
+                let rewritten = apply_subst(template, &subst);
+                let route = structural_classify(state, rewritten.clone());
+                state.log_event("engine_rule_rewrite_template", route, rewritten);
+                return Some(route);
+            }
+
+            // `mu_match_bind` subsumes the plain `_`-wildcard matcher and
+            // also binds `?x`-style captures, which a `guard` can inspect.
+            let mut env: HashMap<String, Mu> = HashMap::new();
+            if !mu_match_bind(&rule.pattern, input, &mut env) {
                 continue;
             }
 
+            if let Some(guard) = &rule.guard {
+                match env.get(&guard.var) {
+                    Some(bound) if bound == &guard.expected => {}
+                    _ => continue,
+                }
+            }
+
+            if let RuleAction::Rewrite(template) = &rule.action {
+                let rewritten = substitute(template, &env);
+                let route = structural_classify(state, rewritten.clone());
+                state.log_event("engine_rule_rewrite", route, rewritten);
+                return Some(route);
+            }
+
             match &rule.action {
                 RuleAction::ToRa => {
                     state.ra.push(input.clone());
@@ -86,13 +133,8 @@ impl Engine {
                     state.log_event("engine_rule_to_sink", RouteKind::Sink, input.clone());
                     return Some(RouteKind::Sink);
                 }
-                RuleAction::Rewrite(new_mu) => {
-                    // Rewrite input → new_mu, then structurally classify that.
-                    let rewritten = new_mu.clone();
-                    let route = structural_classify(state, rewritten.clone());
-                    state.log_event("engine_rule_rewrite", route, rewritten);
-                    return Some(route);
-                }
+                RuleAction::Rewrite(_) => unreachable!("handled above"),
+                RuleAction::RewriteTemplate(_) => unreachable!("handled above"),
             }
         }
 
@@ -115,15 +115,19 @@ pub fn engine_run_to_json(world_name: &str, program: &RcxProgram, inputs: &[Mu])
             r#""payload":{}"#,
             json_escape(&mu_to_string(&evt.payload))
         ));
+        if let Some(t) = &evt.t {
+            out.push_str(&format!(r#","t":{}"#, json_escape(t)));
+        }
         out.push('}');
     }
-    out.push_str("]");
+    out.push(']');
 
     out.push('}');
     out
 }
 
-/// Convenience: parse multiple Mu sources (strings) into a Vec<Mu>.
+// Convenience: parse multiple Mu sources (strings) into a Vec<Mu>; see
+// `parse_inputs` below.
 
 /// Run an Engine starting from an existing RCXState (e.g. restored from a snapshot)
 /// and export the full run as JSON.
@@ -205,9 +209,12 @@ pub fn engine_run_from_state_to_json(
             r#""payload":{}"#,
             json_escape(&mu_to_string(&ev.payload))
         ));
+        if let Some(t) = &ev.t {
+            out.push_str(&format!(r#","t":{}"#, json_escape(t)));
+        }
         out.push('}');
     }
-    out.push_str("]");
+    out.push(']');
 
     out.push('}');
     out
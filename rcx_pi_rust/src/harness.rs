@@ -0,0 +1,559 @@
+//! Directory-based golden test harness for `.mu`/`.rcx` case files.
+//!
+//! Replaces the former bespoke snapshot-roundtrip example and generalizes `orbit_cli`'s
+//! ad-hoc classifier check into a real regression suite: scan a directory of
+//! case files, each of which is itself a `.mu` program, and run it under a
+//! typed `Mode` according to `# @directive` comment lines at its head. Those
+//! lines are ordinary `#`-comments as far as `mu_loader::parse_program` is
+//! concerned, so a case file is simultaneously a valid world and a test.
+//!
+//! Directive syntax (one per comment line, value is the rest of the line):
+//!   `# @mode orbit-classify|engine-route|snapshot-roundtrip|parse-fail`
+//!   `# @seed <Mu>`            (repeatable)
+//!   `# @extra <Mu>`           (snapshot-roundtrip only)
+//!   `# @max-steps <N>`        (orbit-classify only; default 64)
+//!   `# @expect <text>`        (orbit-classify: a classification string;
+//!                              parse-fail: a `MuLoadErrorKind` variant name)
+//!   `# @expect-ra <Mu>`       (engine-route only, repeatable)
+//!   `# @expect-lobes <Mu>`    (engine-route only, repeatable)
+//!   `# @expect-sink <Mu>`     (engine-route only, repeatable)
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::engine::Engine;
+use crate::formatter::mu_to_string;
+use crate::mu_loader::{parse_program, MuLoadErrorKind};
+use crate::orbit::{classify, step};
+use crate::parser::parse_mu;
+use crate::snapshot_json::{snapshot_from_json, snapshot_to_json};
+use crate::state::RCXState;
+use crate::types::Mu;
+
+/// Which kind of check a case file runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Drive `orbit::step` from `@seed` through `orbit::classify` and
+    /// compare the resulting description against `@expect`.
+    OrbitClassify,
+    /// Feed each `@seed` through `Engine::process_input` and compare the
+    /// final `ra`/`lobes`/`sink` buckets against `@expect-ra`/`-lobes`/`-sink`.
+    EngineRoute,
+    /// Run `@seed`s, snapshot, reload, run `@extra`, and assert the buckets
+    /// match a baseline run of `@seed`s + `@extra` without snapshotting.
+    SnapshotRoundtrip,
+    /// Assert `mu_loader::parse_program` rejects the case file's own rule
+    /// lines with the `MuLoadErrorKind` named by `@expect`.
+    ParseFail,
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "orbit-classify" => Ok(Mode::OrbitClassify),
+            "engine-route" => Ok(Mode::EngineRoute),
+            "snapshot-roundtrip" => Ok(Mode::SnapshotRoundtrip),
+            "parse-fail" => Ok(Mode::ParseFail),
+            other => Err(format!("unknown @mode `{other}`")),
+        }
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Mode::OrbitClassify => "orbit-classify",
+            Mode::EngineRoute => "engine-route",
+            Mode::SnapshotRoundtrip => "snapshot-roundtrip",
+            Mode::ParseFail => "parse-fail",
+        })
+    }
+}
+
+/// A case file parsed into its mode and directives, keeping the raw source
+/// around since the modes that need a program re-parse it via `parse_program`.
+pub struct Case {
+    pub path: PathBuf,
+    pub mode: Mode,
+    src: String,
+    seeds: Vec<String>,
+    extra: Option<String>,
+    max_steps: usize,
+    expect: Option<String>,
+    expect_ra: Vec<String>,
+    expect_lobes: Vec<String>,
+    expect_sink: Vec<String>,
+}
+
+/// The keys this mode's `--bless` rewrites; every other directive (and every
+/// non-directive line) is left untouched.
+fn blessable_keys(mode: Mode) -> &'static [&'static str] {
+    match mode {
+        Mode::OrbitClassify | Mode::ParseFail => &["expect"],
+        Mode::EngineRoute => &["expect-ra", "expect-lobes", "expect-sink"],
+        Mode::SnapshotRoundtrip => &[],
+    }
+}
+
+/// `# @key value` -> `Some("key")`; anything else (including plain `#`
+/// comments and rule lines) -> `None`.
+fn directive_key(line: &str) -> Option<&str> {
+    let rest = line.trim().strip_prefix('#')?.trim_start();
+    let rest = rest.strip_prefix('@')?;
+    Some(rest.split_whitespace().next().unwrap_or(""))
+}
+
+fn directive_key_value(line: &str) -> Option<(&str, &str)> {
+    let rest = line.trim().strip_prefix('#')?.trim_start();
+    let rest = rest.strip_prefix('@')?;
+    match rest.split_once(char::is_whitespace) {
+        Some((key, value)) => Some((key, value.trim())),
+        None => Some((rest, "")),
+    }
+}
+
+impl Case {
+    /// Parse a case file's directives out of its `#`-comment lines. The
+    /// mode must be declared via `@mode`; everything else defaults to empty.
+    pub fn parse(path: &Path) -> Result<Case, String> {
+        let src = fs::read_to_string(path)
+            .map_err(|e| format!("read {}: {e}", path.display()))?;
+
+        let mut mode: Option<Mode> = None;
+        let mut seeds = Vec::new();
+        let mut extra = None;
+        let mut max_steps = 64usize;
+        let mut expect = None;
+        let mut expect_ra = Vec::new();
+        let mut expect_lobes = Vec::new();
+        let mut expect_sink = Vec::new();
+
+        for line in src.lines() {
+            let (key, value) = match directive_key_value(line) {
+                Some(kv) => kv,
+                None => continue,
+            };
+            match key {
+                "mode" => mode = Some(value.parse::<Mode>()?),
+                "seed" => seeds.push(value.to_string()),
+                "extra" => extra = Some(value.to_string()),
+                "max-steps" => {
+                    max_steps = value
+                        .parse()
+                        .map_err(|e| format!("bad @max-steps `{value}`: {e}"))?
+                }
+                "expect" => expect = Some(value.to_string()),
+                "expect-ra" => expect_ra.push(value.to_string()),
+                "expect-lobes" => expect_lobes.push(value.to_string()),
+                "expect-sink" => expect_sink.push(value.to_string()),
+                other => return Err(format!("unknown directive `@{other}`")),
+            }
+        }
+
+        let mode = mode.ok_or_else(|| "missing `@mode` directive".to_string())?;
+
+        Ok(Case {
+            path: path.to_path_buf(),
+            mode,
+            src,
+            seeds,
+            extra,
+            max_steps,
+            expect,
+            expect_ra,
+            expect_lobes,
+            expect_sink,
+        })
+    }
+}
+
+/// The outcome of actually running a case, independent of what it expected -
+/// shared between `run_case` (compare against `@expect*`) and `bless_case`
+/// (rewrite `@expect*` from this).
+enum Actual {
+    Classification(String),
+    Buckets {
+        ra: Vec<Mu>,
+        lobes: Vec<Mu>,
+        sink: Vec<Mu>,
+    },
+    RoundtripMatch,
+    RoundtripMismatch(String),
+    ParseFailed(MuLoadErrorKind),
+    ParseSucceeded,
+}
+
+fn kind_name(kind: &MuLoadErrorKind) -> &'static str {
+    match kind {
+        MuLoadErrorKind::MissingArrow => "MissingArrow",
+        MuLoadErrorKind::MultipleArrows => "MultipleArrows",
+        MuLoadErrorKind::BadPattern(_) => "BadPattern",
+        MuLoadErrorKind::UnknownAction(_) => "UnknownAction",
+        MuLoadErrorKind::MalformedRewrite(_) => "MalformedRewrite",
+        MuLoadErrorKind::Io(_) => "Io",
+    }
+}
+
+fn run_actual(case: &Case) -> Result<Actual, String> {
+    match case.mode {
+        Mode::OrbitClassify => {
+            let program = parse_program(&case.src).map_err(|e| e.to_string())?;
+            let seed_src = case
+                .seeds
+                .first()
+                .ok_or_else(|| "orbit-classify needs a @seed".to_string())?;
+            let seed = parse_mu(seed_src).map_err(|e| format!("parse @seed: {e}"))?;
+            let classification = classify(&seed, case.max_steps, |current| step(&program, current));
+            Ok(Actual::Classification(classification))
+        }
+
+        Mode::EngineRoute => {
+            let program = parse_program(&case.src).map_err(|e| e.to_string())?;
+            let mut engine = Engine::new(program);
+            let mut state = RCXState::new();
+            for seed_src in &case.seeds {
+                let seed = parse_mu(seed_src).map_err(|e| format!("parse @seed: {e}"))?;
+                engine.process_input(&mut state, seed);
+            }
+            Ok(Actual::Buckets {
+                ra: state.ra,
+                lobes: state.lobes,
+                sink: state.sink,
+            })
+        }
+
+        Mode::SnapshotRoundtrip => {
+            let program = parse_program(&case.src).map_err(|e| e.to_string())?;
+            let extra_src = case
+                .extra
+                .as_deref()
+                .ok_or_else(|| "snapshot-roundtrip needs an @extra".to_string())?;
+            let extra = parse_mu(extra_src).map_err(|e| format!("parse @extra: {e}"))?;
+
+            let mut seeds = Vec::new();
+            for seed_src in &case.seeds {
+                seeds.push(parse_mu(seed_src).map_err(|e| format!("parse @seed: {e}"))?);
+            }
+
+            let world = case
+                .path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("case");
+
+            let mut engine_a = Engine::new(program.clone());
+            let mut state_a = RCXState::new();
+            for mu in &seeds {
+                engine_a.process_input(&mut state_a, mu.clone());
+            }
+            let snap = snapshot_to_json(world, &program, &state_a);
+
+            let (_, program_b, mut state_b) =
+                snapshot_from_json(world, &snap).map_err(|e| format!("reload snapshot: {e}"))?;
+            let mut engine_b = Engine::new(program_b);
+            engine_b.process_input(&mut state_b, extra.clone());
+
+            let mut engine_c = Engine::new(program);
+            let mut state_c = RCXState::new();
+            for mu in &seeds {
+                engine_c.process_input(&mut state_c, mu.clone());
+            }
+            engine_c.process_input(&mut state_c, extra);
+
+            if state_b.ra == state_c.ra && state_b.lobes == state_c.lobes && state_b.sink == state_c.sink {
+                Ok(Actual::RoundtripMatch)
+            } else {
+                Ok(Actual::RoundtripMismatch(format!(
+                    "after snapshot: ra={:?} lobes={:?} sink={:?}\nbaseline:       ra={:?} lobes={:?} sink={:?}",
+                    state_b.ra, state_b.lobes, state_b.sink, state_c.ra, state_c.lobes, state_c.sink
+                )))
+            }
+        }
+
+        Mode::ParseFail => match parse_program(&case.src) {
+            Ok(_) => Ok(Actual::ParseSucceeded),
+            Err(e) => Ok(Actual::ParseFailed(e.kind)),
+        },
+    }
+}
+
+/// Render `actual` back into the `# @expect...` directive lines `--bless`
+/// should write for this case's mode.
+fn actual_to_directive_lines(case: &Case, actual: &Actual) -> Vec<String> {
+    match actual {
+        Actual::Classification(s) => vec![format!("# @expect {s}")],
+        Actual::Buckets { ra, lobes, sink } => ra
+            .iter()
+            .map(|mu| format!("# @expect-ra {}", mu_to_string(mu)))
+            .chain(lobes.iter().map(|mu| format!("# @expect-lobes {}", mu_to_string(mu))))
+            .chain(sink.iter().map(|mu| format!("# @expect-sink {}", mu_to_string(mu))))
+            .collect(),
+        Actual::ParseFailed(kind) => vec![format!("# @expect {}", kind_name(kind))],
+        Actual::RoundtripMatch | Actual::RoundtripMismatch(_) | Actual::ParseSucceeded => {
+            let _ = case;
+            Vec::new()
+        }
+    }
+}
+
+/// One case's pass/fail outcome, in the style of `conformance::CaseResult`.
+pub struct CaseResult {
+    pub path: PathBuf,
+    pub mode: Mode,
+    pub passed: bool,
+    pub detail: String,
+}
+
+/// Run one case file and compare its actual outcome against its directives.
+pub fn run_case(case: &Case) -> CaseResult {
+    let (passed, detail) = match run_actual(case) {
+        Err(e) => (false, format!("error running case: {e}")),
+        Ok(Actual::Classification(actual)) => match &case.expect {
+            Some(expected) if expected == &actual => (true, "ok".to_string()),
+            Some(expected) => (
+                false,
+                format!("expected classification `{expected}`, got `{actual}`"),
+            ),
+            None => (false, "missing @expect".to_string()),
+        },
+        Ok(Actual::Buckets { ra, lobes, sink }) => {
+            let ra_src: Vec<String> = ra.iter().map(mu_to_string).collect();
+            let lobes_src: Vec<String> = lobes.iter().map(mu_to_string).collect();
+            let sink_src: Vec<String> = sink.iter().map(mu_to_string).collect();
+            if ra_src == case.expect_ra && lobes_src == case.expect_lobes && sink_src == case.expect_sink {
+                (true, "ok".to_string())
+            } else {
+                (
+                    false,
+                    format!(
+                        "expected ra={:?} lobes={:?} sink={:?}\ngot      ra={:?} lobes={:?} sink={:?}",
+                        case.expect_ra, case.expect_lobes, case.expect_sink, ra_src, lobes_src, sink_src
+                    ),
+                )
+            }
+        }
+        Ok(Actual::RoundtripMatch) => (true, "ok".to_string()),
+        Ok(Actual::RoundtripMismatch(detail)) => (false, detail),
+        Ok(Actual::ParseFailed(kind)) => match &case.expect {
+            Some(expected) if expected == kind_name(&kind) => (true, "ok".to_string()),
+            Some(expected) => (
+                false,
+                format!("expected parse failure `{expected}`, got `{}`", kind_name(&kind)),
+            ),
+            None => (false, "missing @expect".to_string()),
+        },
+        Ok(Actual::ParseSucceeded) => (false, "expected parse-fail, but parse_program succeeded".to_string()),
+    };
+
+    CaseResult {
+        path: case.path.clone(),
+        mode: case.mode,
+        passed,
+        detail,
+    }
+}
+
+/// A whole directory's worth of case results.
+pub struct HarnessReport {
+    pub results: Vec<CaseResult>,
+}
+
+impl HarnessReport {
+    pub fn all_passed(&self) -> bool {
+        self.results.iter().all(|r| r.passed)
+    }
+
+    pub fn failures(&self) -> Vec<&CaseResult> {
+        self.results.iter().filter(|r| !r.passed).collect()
+    }
+}
+
+impl fmt::Display for HarnessReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let passed = self.results.iter().filter(|r| r.passed).count();
+        writeln!(f, "{passed}/{} cases passed", self.results.len())?;
+        for failure in self.failures() {
+            writeln!(
+                f,
+                "  FAIL [{}] {}: {}",
+                failure.mode,
+                failure.path.display(),
+                failure.detail
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Case files end in `.mu` or `.rcx`; scanned in sorted order for stable output.
+fn is_case_file(path: &Path) -> bool {
+    matches!(path.extension().and_then(|e| e.to_str()), Some("mu") | Some("rcx"))
+}
+
+fn case_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("read_dir {}: {e}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| is_case_file(p))
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// Parse and run every `.mu`/`.rcx` case file directly under `dir`.
+pub fn run_dir(dir: &Path) -> Result<HarnessReport, String> {
+    let mut results = Vec::new();
+    for path in case_files(dir)? {
+        let case = Case::parse(&path)?;
+        results.push(run_case(&case));
+    }
+    Ok(HarnessReport { results })
+}
+
+/// Re-run every case in `dir` and rewrite its `@expect*` directives to match
+/// actual output, leaving everything else (including non-blessable
+/// directives and the rule lines themselves) untouched. Returns how many
+/// files were rewritten.
+pub fn bless_dir(dir: &Path) -> Result<usize, String> {
+    let mut blessed = 0;
+    for path in case_files(dir)? {
+        let case = Case::parse(&path)?;
+        let actual = run_actual(&case)?;
+        let new_lines = actual_to_directive_lines(&case, &actual);
+        if new_lines.is_empty() {
+            continue;
+        }
+
+        let rewritten = rebless_source(&case.src, blessable_keys(case.mode), &new_lines);
+        if rewritten != case.src {
+            fs::write(&path, rewritten).map_err(|e| format!("write {}: {e}", path.display()))?;
+            blessed += 1;
+        }
+    }
+    Ok(blessed)
+}
+
+/// Replace every directive line whose key is in `blessable` with
+/// `new_lines`, inserted at the position of the first such line (or
+/// appended at the end if none existed). Every other line is untouched.
+fn rebless_source(original: &str, blessable: &[&str], new_lines: &[String]) -> String {
+    let mut out: Vec<String> = Vec::new();
+    let mut inserted = false;
+
+    for line in original.lines() {
+        match directive_key(line) {
+            Some(key) if blessable.contains(&key) => {
+                if !inserted {
+                    out.extend(new_lines.iter().cloned());
+                    inserted = true;
+                }
+            }
+            _ => out.push(line.to_string()),
+        }
+    }
+
+    if !inserted {
+        out.extend(new_lines.iter().cloned());
+    }
+
+    let mut result = out.join("\n");
+    result.push('\n');
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_case(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        let mut f = fs::File::create(&path).unwrap();
+        f.write_all(content.as_bytes()).unwrap();
+        path
+    }
+
+    fn temp_dir(tag: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rcx_harness_test_{tag}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn mode_round_trips_through_display_and_from_str() {
+        for mode in [
+            Mode::OrbitClassify,
+            Mode::EngineRoute,
+            Mode::SnapshotRoundtrip,
+            Mode::ParseFail,
+        ] {
+            assert_eq!(mode.to_string().parse::<Mode>().unwrap(), mode);
+        }
+    }
+
+    #[test]
+    fn orbit_classify_case_passes_when_expectation_matches() {
+        let dir = temp_dir("orbit_ok");
+        write_case(
+            &dir,
+            "swap.mu",
+            "# @mode orbit-classify\n# @seed [SWAP,a,b]\n# @expect pure limit cycle (period = 2)\n[SWAP,x,y] -> rewrite([SWAP,y,x])\n",
+        );
+
+        let report = run_dir(&dir).unwrap();
+        assert_eq!(report.results.len(), 1);
+        assert!(report.all_passed(), "{report}");
+    }
+
+    #[test]
+    fn engine_route_case_fails_with_a_useful_detail_on_mismatch() {
+        let dir = temp_dir("engine_route_bad");
+        write_case(
+            &dir,
+            "route.mu",
+            "# @mode engine-route\n# @seed PING\n# @expect-ra WRONG\nPING -> rewrite(PONG)\n",
+        );
+
+        let report = run_dir(&dir).unwrap();
+        assert!(!report.all_passed());
+        assert!(report.failures()[0].detail.contains("expected ra"));
+    }
+
+    #[test]
+    fn parse_fail_case_checks_the_error_kind() {
+        let dir = temp_dir("parse_fail_ok");
+        write_case(
+            &dir,
+            "bad.mu",
+            "# @mode parse-fail\n# @expect MissingArrow\nnot a rule\n",
+        );
+
+        let report = run_dir(&dir).unwrap();
+        assert!(report.all_passed(), "{report}");
+    }
+
+    #[test]
+    fn bless_rewrites_only_the_expect_line() {
+        let dir = temp_dir("bless");
+        let path = write_case(
+            &dir,
+            "swap.mu",
+            "# @mode orbit-classify\n# @seed [SWAP,a,b]\n# @expect wrong guess\n[SWAP,x,y] -> rewrite([SWAP,y,x])\n",
+        );
+
+        let blessed = bless_dir(&dir).unwrap();
+        assert_eq!(blessed, 1);
+
+        let rewritten = fs::read_to_string(&path).unwrap();
+        assert!(rewritten.contains("# @expect pure limit cycle (period = 2)"));
+        assert!(rewritten.contains("[SWAP,x,y] -> rewrite([SWAP,y,x])"));
+
+        let report = run_dir(&dir).unwrap();
+        assert!(report.all_passed(), "{report}");
+    }
+}
@@ -0,0 +1,70 @@
+//! Bridge from generic `JsonValue` documents into `Mu` terms, so a stream
+//! of JSON events (see `JsonValue::parse_stream`) can be driven straight
+//! through the engine/rule pipeline via `Engine::process_json_stream`.
+
+use crate::json_value::JsonValue;
+use crate::types::Mu;
+
+/// Convert a `JsonValue` into a `Mu` term.
+///
+/// Objects and arrays become `Mu::Node`; every scalar becomes `Mu::Sym`.
+/// An object entry is encoded as a 2-child `[key, value]` node so the
+/// key/value pairing survives the conversion - `JsonValue::Object`'s
+/// `BTreeMap` already gives a deterministic (sorted) key order, so this
+/// is stable across runs.
+pub fn json_to_mu(value: &JsonValue) -> Mu {
+    match value {
+        JsonValue::Null => Mu::Sym("null".to_string()),
+        JsonValue::Bool(b) => Mu::Sym(b.to_string()),
+        JsonValue::Number(n) => Mu::Sym(format!("{n}")),
+        JsonValue::Integer(i) => Mu::Sym(i.to_string()),
+        JsonValue::String(s) => Mu::Sym(s.clone()),
+        JsonValue::Array(items) => Mu::Node(items.iter().map(json_to_mu).collect()),
+        JsonValue::Object(obj) => Mu::Node(
+            obj.iter()
+                .map(|(k, v)| Mu::Node(vec![Mu::Sym(k.clone()), json_to_mu(v)]))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn scalars_become_sym() {
+        assert_eq!(json_to_mu(&JsonValue::Null), Mu::Sym("null".to_string()));
+        assert_eq!(json_to_mu(&JsonValue::Bool(true)), Mu::Sym("true".to_string()));
+        assert_eq!(json_to_mu(&JsonValue::Integer(42)), Mu::Sym("42".to_string()));
+        assert_eq!(
+            json_to_mu(&JsonValue::String("hi".to_string())),
+            Mu::Sym("hi".to_string())
+        );
+    }
+
+    #[test]
+    fn array_becomes_node_of_its_elements() {
+        let v = JsonValue::Array(vec![JsonValue::Integer(1), JsonValue::Integer(2)]);
+        assert_eq!(
+            json_to_mu(&v),
+            Mu::Node(vec![Mu::Sym("1".to_string()), Mu::Sym("2".to_string())])
+        );
+    }
+
+    #[test]
+    fn object_becomes_node_of_key_value_pairs_in_sorted_key_order() {
+        let mut obj = BTreeMap::new();
+        obj.insert("b".to_string(), JsonValue::Integer(2));
+        obj.insert("a".to_string(), JsonValue::Integer(1));
+        let v = JsonValue::Object(obj);
+        assert_eq!(
+            json_to_mu(&v),
+            Mu::Node(vec![
+                Mu::Node(vec![Mu::Sym("a".to_string()), Mu::Sym("1".to_string())]),
+                Mu::Node(vec![Mu::Sym("b".to_string()), Mu::Sym("2".to_string())]),
+            ])
+        );
+    }
+}
@@ -8,7 +8,15 @@ use std::collections::BTreeMap;
 pub enum JsonValue {
     Null,
     Bool(bool),
+    /// A numeric literal written with a `.`, `e`, or `E` in its source text.
+    /// Stored as a correctly-rounded `f64` (see `parse_number`); `-0.0` keeps
+    /// its sign bit and `NaN`/`Infinity` are rejected at parse time rather
+    /// than silently produced by an overflowing exponent.
     Number(f64),
+    /// A numeric literal with no `.`/`e`/`E`, kept as an exact `i128` instead
+    /// of round-tripping through `f64` so counters beyond 2^53 don't
+    /// silently lose precision.
+    Integer(i128),
     String(String),
     Array(Vec<JsonValue>),
     Object(BTreeMap<String, JsonValue>), // BTreeMap for deterministic key order
@@ -21,23 +29,34 @@ impl JsonValue {
         if s.is_empty() {
             return Err("empty input".to_string());
         }
-        let (val, _) = parse_value(s)?;
+        let (val, rest) = parse_value(s)?;
+        let rest = skip_ws(rest);
+        if !rest.is_empty() {
+            return Err(format!("unexpected trailing input: {}", rest));
+        }
         Ok(val)
     }
 
+    /// Iterate over a stream of concatenated or newline-delimited JSON
+    /// documents (NDJSON / "JSON Lines"), yielding one `JsonValue` per
+    /// top-level document.
+    ///
+    /// Unlike `parse`, trailing input after a document is not an error -
+    /// it's simply the start of the next one. A single `,` between
+    /// documents (with optional whitespace around it) is skipped as a
+    /// separator, so both newline-separated and comma-separated streams
+    /// parse cleanly without requiring an enclosing `[...]`.
+    pub fn parse_stream(input: &str) -> JsonStream<'_> {
+        JsonStream { rest: input, offset: 0 }
+    }
+
     /// Serialize to compact canonical JSON (no spaces, sorted keys).
     pub fn to_canonical_json(&self) -> String {
         match self {
             JsonValue::Null => "null".to_string(),
             JsonValue::Bool(b) => if *b { "true" } else { "false" }.to_string(),
-            JsonValue::Number(n) => {
-                // Match Python: integers without decimal, floats with
-                if n.fract() == 0.0 && n.abs() < 1e15 {
-                    format!("{}", *n as i64)
-                } else {
-                    format!("{}", n)
-                }
-            }
+            JsonValue::Number(n) => format_canonical_number(*n),
+            JsonValue::Integer(i) => format!("{}", i),
             JsonValue::String(s) => json_escape_string(s),
             JsonValue::Array(arr) => {
                 let items: Vec<String> = arr.iter().map(|v| v.to_canonical_json()).collect();
@@ -73,6 +92,107 @@ impl JsonValue {
     }
 }
 
+/// Iterator produced by `JsonValue::parse_stream`. Tracks the byte offset
+/// into the original input so a malformed document's error message can
+/// point at where it starts, not just repeat `parse_value`'s "what's left"
+/// message.
+pub struct JsonStream<'a> {
+    rest: &'a str,
+    offset: usize,
+}
+
+impl<'a> Iterator for JsonStream<'a> {
+    type Item = Result<JsonValue, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.skip_separator();
+
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let start_offset = self.offset;
+        match parse_value(self.rest) {
+            Ok((val, rest)) => {
+                self.offset += self.rest.len() - rest.len();
+                self.rest = rest;
+                Some(Ok(val))
+            }
+            Err(e) => {
+                self.rest = "";
+                Some(Err(format!("at byte {start_offset}: {e}")))
+            }
+        }
+    }
+}
+
+impl<'a> JsonStream<'a> {
+    /// Consume whitespace and, at most once, a single `,` separator (plus
+    /// any whitespace around it) between two top-level documents.
+    fn skip_separator(&mut self) {
+        let trimmed = skip_ws(self.rest);
+        self.offset += self.rest.len() - trimmed.len();
+        self.rest = trimmed;
+
+        if let Some(after_comma) = self.rest.strip_prefix(',') {
+            self.offset += self.rest.len() - after_comma.len();
+            self.rest = after_comma;
+            let trimmed = skip_ws(self.rest);
+            self.offset += self.rest.len() - trimmed.len();
+            self.rest = trimmed;
+        }
+    }
+}
+
+/// Render a float in JSON Canonicalization Scheme (RFC 8785) style: the
+/// shortest decimal digit string that round-trips back to the exact same
+/// `f64`, laid out as plain decimal inside `1e-6..1e21` and as scientific
+/// notation outside it - the same thresholds ECMAScript's `Number::toString`
+/// uses.
+///
+/// `-0.0` is kept as `-0.0` rather than collapsed to `0.0`: RFC 8785 treats
+/// them the same, but this codebase's `Number`/`Integer` split already
+/// exists to keep numeric round trips exact (see `parse_number`), so the
+/// sign bit is preserved here too rather than silently dropped.
+fn format_canonical_number(n: f64) -> String {
+    if n == 0.0 {
+        return if n.is_sign_negative() { "-0.0".to_string() } else { "0.0".to_string() };
+    }
+    let negative = n < 0.0;
+    let abs = n.abs();
+
+    // `{:e}` is already the shortest digit string that parses back to
+    // `abs` (Rust's float formatter runs Grisu3 with a Dragon4 fallback for
+    // the rare cases Grisu3 can't resolve) - only the plain-vs-scientific
+    // layout below is this function's own work.
+    let sci = format!("{:e}", abs);
+    let (mantissa, exp_str) = sci.split_once('e').expect("`{:e}` always has an exponent");
+    let exp: i32 = exp_str.parse().expect("`{:e}` exponent is always an integer");
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let num_digits = digits.len() as i32;
+    // `point` is where the decimal point falls relative to `digits`,
+    // i.e. `n` in ECMA-262's `Number::toString` algorithm.
+    let point = exp + 1;
+
+    let body = if point >= num_digits && point <= 21 {
+        format!("{digits}{}", "0".repeat((point - num_digits) as usize))
+    } else if point > 0 && point < num_digits {
+        format!("{}.{}", &digits[..point as usize], &digits[point as usize..])
+    } else if point > -6 && point <= 0 {
+        format!("0.{}{digits}", "0".repeat((-point) as usize))
+    } else {
+        let exp_sign = if point > 0 { "+" } else { "-" };
+        let rendered_exp = (point - 1).abs();
+        if num_digits == 1 {
+            format!("{digits}e{exp_sign}{rendered_exp}")
+        } else {
+            format!("{}.{}e{exp_sign}{rendered_exp}", &digits[..1], &digits[1..])
+        }
+    };
+
+    if negative { format!("-{body}") } else { body }
+}
+
 fn json_escape_string(s: &str) -> String {
     let mut out = String::with_capacity(s.len() + 2);
     out.push('"');
@@ -114,29 +234,45 @@ fn skip_ws(s: &str) -> &str {
 }
 
 fn parse_null(s: &str) -> Result<(JsonValue, &str), String> {
-    if s.starts_with("null") {
-        Ok((JsonValue::Null, &s[4..]))
+    if let Some(rest) = s.strip_prefix("null") {
+        Ok((JsonValue::Null, rest))
     } else {
         Err("expected 'null'".to_string())
     }
 }
 
 fn parse_true(s: &str) -> Result<(JsonValue, &str), String> {
-    if s.starts_with("true") {
-        Ok((JsonValue::Bool(true), &s[4..]))
+    if let Some(rest) = s.strip_prefix("true") {
+        Ok((JsonValue::Bool(true), rest))
     } else {
         Err("expected 'true'".to_string())
     }
 }
 
 fn parse_false(s: &str) -> Result<(JsonValue, &str), String> {
-    if s.starts_with("false") {
-        Ok((JsonValue::Bool(false), &s[5..]))
+    if let Some(rest) = s.strip_prefix("false") {
+        Ok((JsonValue::Bool(false), rest))
     } else {
         Err("expected 'false'".to_string())
     }
 }
 
+/// Reads exactly 4 hex digits from `chars`, advancing `consumed` by one
+/// per digit, and returns the parsed code unit.
+fn read_hex4(chars: &mut std::iter::Peekable<std::str::Chars>, consumed: &mut usize) -> Result<u32, String> {
+    let mut hex = String::new();
+    for _ in 0..4 {
+        match chars.next() {
+            Some(c) if c.is_ascii_hexdigit() => {
+                hex.push(c);
+                *consumed += 1;
+            }
+            _ => return Err("invalid unicode escape".to_string()),
+        }
+    }
+    u32::from_str_radix(&hex, 16).map_err(|_| "invalid unicode escape".to_string())
+}
+
 fn parse_string(s: &str) -> Result<(JsonValue, &str), String> {
     if !s.starts_with('"') {
         return Err("expected '\"'".to_string());
@@ -182,20 +318,32 @@ fn parse_string(s: &str) -> Result<(JsonValue, &str), String> {
                     }
                     Some('u') => {
                         consumed += 1;
-                        let mut hex = String::new();
-                        for _ in 0..4 {
-                            match chars.next() {
-                                Some(c) if c.is_ascii_hexdigit() => {
-                                    hex.push(c);
-                                    consumed += 1;
+                        let code = read_hex4(&mut chars, &mut consumed)?;
+                        match code {
+                            0xD800..=0xDBFF => {
+                                // High surrogate: must be followed by a low
+                                // surrogate escape to combine into one scalar.
+                                if chars.next() != Some('\\') || chars.next() != Some('u') {
+                                    return Err("unpaired surrogate in unicode escape".to_string());
+                                }
+                                consumed += 2;
+                                let low = read_hex4(&mut chars, &mut consumed)?;
+                                if !(0xDC00..=0xDFFF).contains(&low) {
+                                    return Err("unpaired surrogate in unicode escape".to_string());
                                 }
-                                _ => return Err("invalid unicode escape".to_string()),
+                                let combined =
+                                    0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                                let c = char::from_u32(combined)
+                                    .ok_or("invalid unicode escape")?;
+                                result.push(c);
+                            }
+                            0xDC00..=0xDFFF => {
+                                return Err("unpaired surrogate in unicode escape".to_string());
+                            }
+                            _ => {
+                                let c = char::from_u32(code).ok_or("invalid unicode escape")?;
+                                result.push(c);
                             }
-                        }
-                        let code = u32::from_str_radix(&hex, 16)
-                            .map_err(|_| "invalid unicode escape")?;
-                        if let Some(c) = char::from_u32(code) {
-                            result.push(c);
                         }
                     }
                     Some(c) => {
@@ -219,12 +367,17 @@ fn parse_number(s: &str) -> Result<(JsonValue, &str), String> {
     let mut end = 0;
     let chars: Vec<char> = s.chars().collect();
 
-    // Optional minus
-    if end < chars.len() && chars[end] == '-' {
+    // Optional minus (a leading '+' is not valid JSON and is rejected by
+    // falling through to "expected digit" below).
+    let negative = end < chars.len() && chars[end] == '-';
+    if negative {
         end += 1;
     }
 
-    // Integer part
+    // Integer part: a lone '0', or a non-zero digit followed by more
+    // digits. A leading zero followed by another digit (e.g. "01") is
+    // rejected by simply not consuming past the first '0'.
+    let int_start = end;
     if end >= chars.len() {
         return Err("expected digit".to_string());
     }
@@ -237,9 +390,13 @@ fn parse_number(s: &str) -> Result<(JsonValue, &str), String> {
     } else {
         return Err("expected digit".to_string());
     }
+    let int_end = end;
+
+    let mut is_float = false;
 
     // Fraction
     if end < chars.len() && chars[end] == '.' {
+        is_float = true;
         end += 1;
         if end >= chars.len() || !chars[end].is_ascii_digit() {
             return Err("expected digit after decimal".to_string());
@@ -251,6 +408,7 @@ fn parse_number(s: &str) -> Result<(JsonValue, &str), String> {
 
     // Exponent
     if end < chars.len() && (chars[end] == 'e' || chars[end] == 'E') {
+        is_float = true;
         end += 1;
         if end < chars.len() && (chars[end] == '+' || chars[end] == '-') {
             end += 1;
@@ -265,11 +423,34 @@ fn parse_number(s: &str) -> Result<(JsonValue, &str), String> {
 
     let num_str: String = chars[..end].iter().collect();
     let byte_len: usize = num_str.len();
-    let num: f64 = num_str
-        .parse()
-        .map_err(|_| format!("invalid number: {}", num_str))?;
 
-    Ok((JsonValue::Number(num), &s[byte_len..]))
+    if is_float {
+        // `str::parse::<f64>` is a correctly-rounded decimal-to-binary
+        // conversion (Rust's `dec2flt` runs the same Eisel-Lemire
+        // fast path with a big-integer slow-path fallback that
+        // serde_json's lexical module uses), so literals inside f64
+        // range round to the nearest representable double with ties
+        // to even for free. What it won't do is reject a magnitude
+        // that has no finite double to round to (e.g. `1e400`), so
+        // that has to be checked explicitly - JSON has no `Infinity`
+        // literal, and the grammar above already keeps `NaN` out.
+        let num: f64 = num_str
+            .parse()
+            .map_err(|_| format!("invalid number: {}", num_str))?;
+        if !num.is_finite() {
+            return Err(format!("number out of representable range: {}", num_str));
+        }
+        return Ok((JsonValue::Number(num), &s[byte_len..]));
+    }
+
+    let digits: String = chars[int_start..int_end].iter().collect();
+    let mut value: i128 = digits
+        .parse()
+        .map_err(|_| format!("integer overflows i128: {}", num_str))?;
+    if negative {
+        value = -value;
+    }
+    Ok((JsonValue::Integer(value), &s[byte_len..]))
 }
 
 fn parse_array(s: &str) -> Result<(JsonValue, &str), String> {
@@ -279,8 +460,8 @@ fn parse_array(s: &str) -> Result<(JsonValue, &str), String> {
     let mut s = skip_ws(&s[1..]);
     let mut items = Vec::new();
 
-    if s.starts_with(']') {
-        return Ok((JsonValue::Array(items), &s[1..]));
+    if let Some(rest) = s.strip_prefix(']') {
+        return Ok((JsonValue::Array(items), rest));
     }
 
     loop {
@@ -288,8 +469,8 @@ fn parse_array(s: &str) -> Result<(JsonValue, &str), String> {
         items.push(val);
         s = skip_ws(rest);
 
-        if s.starts_with(']') {
-            return Ok((JsonValue::Array(items), &s[1..]));
+        if let Some(rest) = s.strip_prefix(']') {
+            return Ok((JsonValue::Array(items), rest));
         } else if s.starts_with(',') {
             s = skip_ws(&s[1..]);
         } else {
@@ -305,8 +486,8 @@ fn parse_object(s: &str) -> Result<(JsonValue, &str), String> {
     let mut s = skip_ws(&s[1..]);
     let mut obj = BTreeMap::new();
 
-    if s.starts_with('}') {
-        return Ok((JsonValue::Object(obj), &s[1..]));
+    if let Some(rest) = s.strip_prefix('}') {
+        return Ok((JsonValue::Object(obj), rest));
     }
 
     loop {
@@ -329,8 +510,8 @@ fn parse_object(s: &str) -> Result<(JsonValue, &str), String> {
         obj.insert(key, val);
         s = skip_ws(rest);
 
-        if s.starts_with('}') {
-            return Ok((JsonValue::Object(obj), &s[1..]));
+        if let Some(rest) = s.strip_prefix('}') {
+            return Ok((JsonValue::Object(obj), rest));
         } else if s.starts_with(',') {
             s = skip_ws(&s[1..]);
         } else {
@@ -348,7 +529,7 @@ mod tests {
         assert_eq!(JsonValue::parse("null").unwrap(), JsonValue::Null);
         assert_eq!(JsonValue::parse("true").unwrap(), JsonValue::Bool(true));
         assert_eq!(JsonValue::parse("false").unwrap(), JsonValue::Bool(false));
-        assert_eq!(JsonValue::parse("42").unwrap(), JsonValue::Number(42.0));
+        assert_eq!(JsonValue::parse("42").unwrap(), JsonValue::Integer(42));
         assert_eq!(JsonValue::parse("-3.14").unwrap(), JsonValue::Number(-3.14));
         assert_eq!(
             JsonValue::parse("\"hello\"").unwrap(),
@@ -371,7 +552,7 @@ mod tests {
         let obj = JsonValue::parse(r#"{"a":1,"b":2}"#).unwrap();
         if let JsonValue::Object(map) = obj {
             assert_eq!(map.len(), 2);
-            assert_eq!(map.get("a"), Some(&JsonValue::Number(1.0)));
+            assert_eq!(map.get("a"), Some(&JsonValue::Integer(1)));
         } else {
             panic!("expected object");
         }
@@ -383,4 +564,131 @@ mod tests {
         let obj = JsonValue::parse(r#"{"z":1,"a":2}"#).unwrap();
         assert_eq!(obj.to_canonical_json(), r#"{"a":2,"z":1}"#);
     }
+
+    #[test]
+    fn large_integer_survives_round_trip_past_f64_precision() {
+        // 2^53 + 1 is the smallest integer an f64 cannot represent exactly.
+        let big = "9007199254740993";
+        let val = JsonValue::parse(big).unwrap();
+        assert_eq!(val, JsonValue::Integer(9007199254740993));
+        assert_eq!(val.to_canonical_json(), big);
+    }
+
+    #[test]
+    fn negative_integer_round_trips() {
+        let val = JsonValue::parse("-42").unwrap();
+        assert_eq!(val, JsonValue::Integer(-42));
+        assert_eq!(val.to_canonical_json(), "-42");
+    }
+
+    #[test]
+    fn float_literal_stays_a_number_not_an_integer() {
+        assert_eq!(JsonValue::parse("1.0").unwrap(), JsonValue::Number(1.0));
+        assert_eq!(JsonValue::parse("1e3").unwrap(), JsonValue::Number(1000.0));
+    }
+
+    #[test]
+    fn surrogate_pair_escape_decodes_to_non_bmp_char() {
+        // U+1F600 GRINNING FACE, encoded as the UTF-16 surrogate pair
+        // 0xD83D 0xDE00 per the JSON spec.
+        let val = JsonValue::parse(r#""\uD83D\uDE00""#).unwrap();
+        assert_eq!(val, JsonValue::String("\u{1F600}".to_string()));
+    }
+
+    #[test]
+    fn lone_high_surrogate_escape_is_rejected() {
+        let err = JsonValue::parse(r#""\uD83D""#).unwrap_err();
+        assert!(err.contains("unpaired surrogate"));
+    }
+
+    #[test]
+    fn lone_low_surrogate_escape_is_rejected() {
+        let err = JsonValue::parse(r#""\uDE00""#).unwrap_err();
+        assert!(err.contains("unpaired surrogate"));
+    }
+
+    #[test]
+    fn leading_zero_is_rejected() {
+        // "0" parses as a complete integer token, leaving "12" as unconsumed
+        // trailing input - exactly how JSON forbids "012" as one number.
+        let err = JsonValue::parse("012").unwrap_err();
+        assert!(err.contains("trailing input"));
+    }
+
+    #[test]
+    fn leading_plus_is_rejected() {
+        assert!(JsonValue::parse("+1").is_err());
+    }
+
+    #[test]
+    fn integer_overflowing_i128_is_an_error() {
+        let too_big = "1".repeat(60);
+        assert!(JsonValue::parse(&too_big).is_err());
+    }
+
+    #[test]
+    fn exponent_overflowing_f64_is_an_error() {
+        let err = JsonValue::parse("1e400").unwrap_err();
+        assert!(err.contains("out of representable range"));
+    }
+
+    #[test]
+    fn parse_stream_yields_one_value_per_ndjson_line() {
+        let docs: Result<Vec<JsonValue>, String> =
+            JsonValue::parse_stream("1\n\"two\"\n[3]\n").collect();
+        assert_eq!(
+            docs.unwrap(),
+            vec![
+                JsonValue::Integer(1),
+                JsonValue::String("two".to_string()),
+                JsonValue::Array(vec![JsonValue::Integer(3)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_stream_accepts_comma_separated_documents() {
+        let docs: Result<Vec<JsonValue>, String> =
+            JsonValue::parse_stream("1,2,3").collect();
+        assert_eq!(
+            docs.unwrap(),
+            vec![JsonValue::Integer(1), JsonValue::Integer(2), JsonValue::Integer(3)]
+        );
+    }
+
+    #[test]
+    fn parse_stream_reports_byte_offset_of_malformed_document() {
+        let err = JsonValue::parse_stream("1\n{bad}")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err();
+        assert!(err.starts_with("at byte 2:"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn canonical_number_formatting_matches_rfc8785_pinned_values() {
+        assert_eq!(JsonValue::Number(0.1).to_canonical_json(), "0.1");
+        assert_eq!(JsonValue::Number(1e21).to_canonical_json(), "1e+21");
+        assert_eq!(
+            JsonValue::Number(100000000000000000000.0).to_canonical_json(),
+            "100000000000000000000"
+        );
+    }
+
+    #[test]
+    fn canonical_number_formatting_round_trips_through_parse() {
+        for n in [0.1, 1e21, 1e20, 1e-6, 1e-7, 123.456, -0.5, 1.5e300] {
+            let rendered = JsonValue::Number(n).to_canonical_json();
+            assert_eq!(rendered.parse::<f64>().unwrap(), n, "round trip of {n} via {rendered}");
+        }
+    }
+
+    #[test]
+    fn negative_zero_keeps_its_sign_through_canonical_json() {
+        let val = JsonValue::parse("-0.0").unwrap();
+        assert_eq!(val, JsonValue::Number(-0.0));
+        assert_eq!(val.to_canonical_json(), "-0.0");
+
+        let pos = JsonValue::parse("0.0").unwrap();
+        assert_eq!(pos.to_canonical_json(), "0.0");
+    }
 }
@@ -13,25 +13,47 @@ mod tests {
     }
 }
 
+pub mod clock;
+pub mod confluence;
+pub mod conformance;
+pub mod congruence;
 pub mod engine;
 pub mod engine_json;
 pub mod eval;
 pub mod fold;
 pub mod formatter;
+pub mod harness;
+pub mod json_mu;
+pub mod json_value;
+pub mod lint;
 pub mod lobes;
 pub mod matching;
+pub mod metrics;
+pub mod mu_codec;
 pub mod mu_loader;
+pub mod mu_path;
+pub mod mu_schema;
 pub mod orbit;
 pub mod orbit_json;
 pub mod parser;
 pub mod pattern;
+pub mod protocol;
+pub mod query;
+pub mod repl;
+pub mod replay_cli;
+pub mod rewrite_graph;
+pub mod rule_dsl;
 pub mod runtime;
+pub mod schemas;
 pub mod serialize;
 pub mod serialize_json;
+pub mod session_io;
 pub mod sink;
+pub mod snapshot_json;
 pub mod state;
 pub mod state_io;
 pub mod trace;
+pub mod trace_canon;
 pub mod traits;
 pub mod types;
 pub mod unify;
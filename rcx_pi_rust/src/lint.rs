@@ -0,0 +1,465 @@
+//! Structural linting for `RcxProgram`s and raw `.mu` rule lines.
+//!
+//! Unlike `parse_rule_line`, which only ever fails outright, this module
+//! produces structured `Diagnostic`s: a severity, the offending rule index,
+//! a message, and (where possible) a `Fix` that `apply_fixes` can apply
+//! automatically. Checks cover rules shadowed by an earlier identical or
+//! more general pattern (unreachable, fixed by reordering), same-pattern
+//! rules with conflicting actions, exact duplicate rules, and `Rewrite`
+//! rules whose RHS re-matches their own LHS (no-ops, and more generally
+//! potentially non-terminating rewrites). Malformed `.mu` lines with an
+//! unrecognized rule target get a "did you mean" suggestion computed by
+//! Levenshtein distance against the known targets.
+
+use crate::formatter::mu_to_string;
+use crate::pattern::mu_matches;
+use crate::snapshot_json::{parse_rule_line, rule_to_string};
+use crate::types::{Mu, RcxProgram, RcxRule, RuleAction};
+
+/// How serious a diagnostic is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    fn as_str(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// A suggested repair for a diagnostic, applied by `apply_fixes`.
+#[derive(Debug, Clone)]
+pub enum Fix {
+    /// Drop the rule at this index (duplicate, conflicting action, or no-op).
+    RemoveRule(usize),
+    /// Replace the rule at this index with a corrected one.
+    ReplaceRule(usize, RcxRule),
+    /// Reorder rule `from` to sit immediately before rule `before`, so a
+    /// specific rule shadowed by an earlier more-general one becomes
+    /// reachable again without losing it.
+    MoveRuleBefore { from: usize, before: usize },
+}
+
+/// One finding from linting a program or a raw rule line.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub rule_index: Option<usize>,
+    pub message: String,
+    pub fix: Option<Fix>,
+}
+
+const KNOWN_TARGETS: [&str; 4] = ["ra", "lobe", "sink", "rewrite"];
+
+/// Classic Levenshtein edit distance (insert/delete/substitute cost 1).
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut dp = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in dp.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in dp[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            dp[i][j] = if a[i - 1] == b[j - 1] {
+                dp[i - 1][j - 1]
+            } else {
+                1 + dp[i - 1][j - 1].min(dp[i - 1][j]).min(dp[i][j - 1])
+            };
+        }
+    }
+    dp[a.len()][b.len()]
+}
+
+/// Find the closest known rule target (`ra`, `lobe`, `sink`, `rewrite`) to
+/// `unknown`, if it's within edit distance 2.
+pub fn suggest_target(unknown: &str) -> Option<&'static str> {
+    KNOWN_TARGETS
+        .iter()
+        .map(|&target| (target, levenshtein(unknown, target)))
+        .filter(|&(_, distance)| distance <= 2)
+        .min_by_key(|&(_, distance)| distance)
+        .map(|(target, _)| target)
+}
+
+/// Parse one `.mu` rule line, turning an `unknown rule target` failure into
+/// a diagnostic carrying a "did you mean" fix instead of a bare error string.
+pub fn lint_rule_line(line: &str, index: usize) -> Result<RcxRule, Box<Diagnostic>> {
+    parse_rule_line(line).map_err(|message| {
+        let unknown = message
+            .strip_prefix("unknown rule target `")
+            .and_then(|rest| rest.strip_suffix('`'));
+        let suggestion = unknown.and_then(suggest_target);
+
+        let fix = suggestion.and_then(|target| {
+            let pat_src = line.split("->").next()?.trim();
+            let pattern = crate::parser::parse_mu(pat_src).ok()?;
+            let action = match target {
+                "ra" => RuleAction::ToRa,
+                "lobe" => RuleAction::ToLobe,
+                "sink" => RuleAction::ToSink,
+                // "rewrite" needs a payload term we don't have from a typo
+                // alone, so it's diagnosed but left for the author to fix.
+                _ => return None,
+            };
+            Some(Fix::ReplaceRule(index, RcxRule::new(pattern, action)))
+        });
+
+        let message = match suggestion {
+            Some(target) => format!("{message} (did you mean `{target}`?)"),
+            None => message,
+        };
+
+        Box::new(Diagnostic {
+            severity: Severity::Error,
+            rule_index: Some(index),
+            message,
+            fix,
+        })
+    })
+}
+
+/// Does `general` match every value that `specific` matches? Mirrors the
+/// `_`-wildcard semantics of `pattern::mu_matches`, so it can find rules
+/// that can never fire because an earlier rule's pattern already covers them.
+fn pattern_subsumes(general: &Mu, specific: &Mu) -> bool {
+    match general {
+        Mu::Sym(s) if s == "_" => true,
+        Mu::Sym(g) => matches!(specific, Mu::Sym(s) if s == g),
+        Mu::Node(g_children) => match specific {
+            Mu::Node(s_children) => {
+                g_children.len() == s_children.len()
+                    && g_children
+                        .iter()
+                        .zip(s_children.iter())
+                        .all(|(g, s)| pattern_subsumes(g, s))
+            }
+            Mu::Sym(_) => false,
+        },
+    }
+}
+
+/// Lint an `RcxProgram`, surfacing unreachable rules (shadowed by an
+/// earlier identical or more general pattern), exact duplicates,
+/// same-pattern rules with conflicting actions, and no-op or potentially
+/// non-terminating rewrites.
+pub fn lint_program(program: &RcxProgram) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for (i, rule) in program.rules.iter().enumerate() {
+        if let Some(j) = program.rules[..i].iter().position(|earlier| earlier == rule) {
+            diagnostics.push(Diagnostic {
+                severity: Severity::Warning,
+                rule_index: Some(i),
+                message: format!("rule {i} is an exact duplicate of rule {j}"),
+                fix: Some(Fix::RemoveRule(i)),
+            });
+        } else if let Some(j) = program.rules[..i]
+            .iter()
+            .position(|earlier| earlier.pattern == rule.pattern && earlier.action != rule.action)
+        {
+            diagnostics.push(Diagnostic {
+                severity: Severity::Error,
+                rule_index: Some(i),
+                message: format!(
+                    "rule {i} has the same pattern as rule {j} but a conflicting action; rule {j} always wins"
+                ),
+                fix: Some(Fix::RemoveRule(i)),
+            });
+        } else if let Some(j) = program.rules[..i]
+            .iter()
+            .position(|earlier| pattern_subsumes(&earlier.pattern, &rule.pattern))
+        {
+            diagnostics.push(Diagnostic {
+                severity: Severity::Warning,
+                rule_index: Some(i),
+                message: format!(
+                    "rule {i} is unreachable: shadowed by rule {j}'s pattern `{}`",
+                    mu_to_string(&program.rules[j].pattern)
+                ),
+                fix: Some(Fix::MoveRuleBefore { from: i, before: j }),
+            });
+        }
+
+        if let RuleAction::Rewrite(rhs) = &rule.action {
+            if rhs == &rule.pattern {
+                diagnostics.push(Diagnostic {
+                    severity: Severity::Warning,
+                    rule_index: Some(i),
+                    message: format!("rule {i} is a no-op: rewrite RHS equals its LHS"),
+                    fix: Some(Fix::RemoveRule(i)),
+                });
+            } else if mu_matches(&rule.pattern, rhs) {
+                diagnostics.push(Diagnostic {
+                    severity: Severity::Warning,
+                    rule_index: Some(i),
+                    message: format!(
+                        "rule {i} is potentially non-terminating: its rewrite RHS `{}` still matches its own pattern `{}`, so it can fire again indefinitely",
+                        mu_to_string(rhs),
+                        mu_to_string(&rule.pattern)
+                    ),
+                    fix: None,
+                });
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// Render diagnostics as `[severity] rule N: message` lines, one per entry.
+pub fn format_diagnostics(diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(|d| match d.rule_index {
+            Some(i) => format!("[{}] rule {i}: {}", d.severity.as_str(), d.message),
+            None => format!("[{}] {}", d.severity.as_str(), d.message),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Like `format_diagnostics`, but grouped under an "errors:"/"warnings:"
+/// heading per severity (errors first) instead of interleaved in rule
+/// order. Used by the REPL's `:check` command.
+pub fn format_diagnostics_grouped(diagnostics: &[Diagnostic]) -> String {
+    let mut sections = Vec::new();
+    for (severity, heading) in [(Severity::Error, "errors"), (Severity::Warning, "warnings")] {
+        let group: Vec<&Diagnostic> = diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .collect();
+        if group.is_empty() {
+            continue;
+        }
+        let mut lines = vec![format!("{heading}:")];
+        for d in group {
+            match d.rule_index {
+                Some(i) => lines.push(format!("  rule {i}: {}", d.message)),
+                None => lines.push(format!("  {}", d.message)),
+            }
+        }
+        sections.push(lines.join("\n"));
+    }
+    sections.join("\n")
+}
+
+fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Render diagnostics as a JSON array so CI can consume lint results without
+/// scraping human text.
+pub fn diagnostics_to_json(diagnostics: &[Diagnostic]) -> String {
+    let mut out = String::from("[");
+    for (i, d) in diagnostics.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push('{');
+        out.push_str(&format!(r#""severity":{},"#, json_escape(d.severity.as_str())));
+        match d.rule_index {
+            Some(idx) => out.push_str(&format!(r#""rule_index":{idx},"#)),
+            None => out.push_str(r#""rule_index":null,"#),
+        }
+        out.push_str(&format!(r#""message":{},"#, json_escape(&d.message)));
+        out.push_str(&format!(
+            r#""has_fix":{}"#,
+            if d.fix.is_some() { "true" } else { "false" }
+        ));
+        out.push('}');
+    }
+    out.push(']');
+    out
+}
+
+/// Apply every `fix` present in `diagnostics` to `program`, rebuilding rule
+/// text through `rule_to_string` so the result stays in the same textual
+/// form the `.mu` loader would have produced. Rules marked `RemoveRule` are
+/// dropped; rules marked `ReplaceRule` are swapped in place; rules marked
+/// `MoveRuleBefore` are reordered ahead of the rule that was shadowing
+/// them. Diagnostics without a fix (e.g. unresolved rewrite-target typos,
+/// non-terminating rewrites) are left untouched.
+pub fn apply_fixes(program: &RcxProgram, diagnostics: &[Diagnostic]) -> RcxProgram {
+    let mut removed = vec![false; program.rules.len()];
+    let mut replacements: Vec<Option<RcxRule>> = vec![None; program.rules.len()];
+    let mut order: Vec<usize> = (0..program.rules.len()).collect();
+
+    for d in diagnostics {
+        match &d.fix {
+            Some(Fix::RemoveRule(i)) => removed[*i] = true,
+            Some(Fix::ReplaceRule(i, rule)) => replacements[*i] = Some(rule.clone()),
+            Some(Fix::MoveRuleBefore { from, before }) => {
+                if let Some(from_pos) = order.iter().position(|&idx| idx == *from) {
+                    let from_idx = order.remove(from_pos);
+                    let before_pos = order
+                        .iter()
+                        .position(|&idx| idx == *before)
+                        .unwrap_or(order.len());
+                    order.insert(before_pos, from_idx);
+                }
+            }
+            None => {}
+        }
+    }
+
+    let rules = order
+        .into_iter()
+        .filter(|i| !removed[*i])
+        .map(|i| match replacements[i].take() {
+            Some(replacement) => replacement,
+            None => {
+                let rule = &program.rules[i];
+                // Round-trip through text form to stay consistent with how
+                // a fixed-up `.mu` file would be re-emitted.
+                let text = rule_to_string(rule);
+                parse_rule_line(&text).unwrap_or_else(|_| rule.clone())
+            }
+        })
+        .collect();
+
+    RcxProgram::new(rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levenshtein_matches_known_cases() {
+        assert_eq!(levenshtein("ra", "ra"), 0);
+        assert_eq!(levenshtein("rewrte", "rewrite"), 1);
+        assert_eq!(levenshtein("sinkk", "sink"), 1);
+    }
+
+    #[test]
+    fn suggest_target_finds_close_typo() {
+        assert_eq!(suggest_target("lobbe"), Some("lobe"));
+        assert_eq!(suggest_target("xyzzyplugh"), None);
+    }
+
+    #[test]
+    fn lint_rule_line_suggests_fix_for_typo_target() {
+        let err = lint_rule_line("a -> sinkk", 0).unwrap_err();
+        assert!(err.message.contains("did you mean `sink`?"));
+        assert!(matches!(err.fix, Some(Fix::ReplaceRule(0, _))));
+    }
+
+    #[test]
+    fn lint_program_flags_unreachable_rule() {
+        let program = RcxProgram::new(vec![
+            RcxRule::new(Mu::Sym("_".to_string()), RuleAction::ToSink),
+            RcxRule::new(Mu::Sym("A".to_string()), RuleAction::ToRa),
+        ]);
+        let diagnostics = lint_program(&program);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("unreachable"));
+    }
+
+    #[test]
+    fn lint_program_flags_exact_duplicate() {
+        let rule = RcxRule::new(Mu::Sym("A".to_string()), RuleAction::ToRa);
+        let program = RcxProgram::new(vec![rule.clone(), rule]);
+        let diagnostics = lint_program(&program);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("duplicate"));
+    }
+
+    #[test]
+    fn lint_program_flags_conflicting_action_on_same_pattern() {
+        let program = RcxProgram::new(vec![
+            RcxRule::new(Mu::Sym("A".to_string()), RuleAction::ToRa),
+            RcxRule::new(Mu::Sym("A".to_string()), RuleAction::ToSink),
+        ]);
+        let diagnostics = lint_program(&program);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert!(diagnostics[0].message.contains("conflicting action"));
+        assert!(matches!(diagnostics[0].fix, Some(Fix::RemoveRule(1))));
+    }
+
+    #[test]
+    fn lint_program_flags_noop_rewrite() {
+        let program = RcxProgram::new(vec![RcxRule::new(
+            Mu::Sym("B".to_string()),
+            RuleAction::Rewrite(Mu::Sym("B".to_string())),
+        )]);
+        let diagnostics = lint_program(&program);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("no-op"));
+    }
+
+    #[test]
+    fn lint_program_flags_potentially_non_terminating_rewrite() {
+        let program = RcxProgram::new(vec![RcxRule::new(
+            Mu::Sym("_".to_string()),
+            RuleAction::Rewrite(Mu::Sym("B".to_string())),
+        )]);
+        let diagnostics = lint_program(&program);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("non-terminating"));
+        assert!(diagnostics[0].fix.is_none());
+    }
+
+    #[test]
+    fn apply_fixes_removes_conflicting_action_rule() {
+        let program = RcxProgram::new(vec![
+            RcxRule::new(Mu::Sym("A".to_string()), RuleAction::ToRa),
+            RcxRule::new(Mu::Sym("A".to_string()), RuleAction::ToSink),
+        ]);
+        let diagnostics = lint_program(&program);
+        let fixed = apply_fixes(&program, &diagnostics);
+        assert_eq!(fixed.rules.len(), 1);
+        assert_eq!(fixed.rules[0].action, RuleAction::ToRa);
+    }
+
+    #[test]
+    fn format_diagnostics_grouped_puts_errors_before_warnings() {
+        let program = RcxProgram::new(vec![
+            RcxRule::new(Mu::Sym("A".to_string()), RuleAction::ToRa),
+            RcxRule::new(Mu::Sym("A".to_string()), RuleAction::ToSink),
+            RcxRule::new(Mu::Sym("B".to_string()), RuleAction::Rewrite(Mu::Sym("B".to_string()))),
+        ]);
+        let diagnostics = lint_program(&program);
+        let rendered = format_diagnostics_grouped(&diagnostics);
+        let errors_at = rendered.find("errors:").unwrap();
+        let warnings_at = rendered.find("warnings:").unwrap();
+        assert!(errors_at < warnings_at);
+    }
+
+    #[test]
+    fn apply_fixes_reorders_unreachable_rule_before_its_shadow() {
+        let program = RcxProgram::new(vec![
+            RcxRule::new(Mu::Sym("_".to_string()), RuleAction::ToSink),
+            RcxRule::new(Mu::Sym("A".to_string()), RuleAction::ToRa),
+        ]);
+        let diagnostics = lint_program(&program);
+        assert!(matches!(
+            diagnostics[0].fix,
+            Some(Fix::MoveRuleBefore { from: 1, before: 0 })
+        ));
+        let fixed = apply_fixes(&program, &diagnostics);
+        assert_eq!(fixed.rules.len(), 2);
+        assert_eq!(fixed.rules[0].pattern, Mu::Sym("A".to_string()));
+        assert_eq!(fixed.rules[1].pattern, Mu::Sym("_".to_string()));
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::types::Mu;
 
@@ -6,22 +6,33 @@ use crate::types::Mu;
 pub type Env = HashMap<String, Mu>;
 
 /// Our convention:
-///   • A symbol is a *pattern variable* iff it is a single lowercase ASCII letter,
-///     e.g. "x", "y", "z".
+///   • A symbol is a *pattern variable* iff it starts with a single lowercase
+///     ASCII letter optionally followed by ASCII digits, e.g. "x", "y", "z",
+///     or a freshened "x1", "x2" (see [`FreshVars`]).
+///   • "_" is an anonymous wildcard: it matches anything but is never bound,
+///     so repeated "_"s in one pattern don't have to agree with each other.
 ///   • Everything else ("ping", "pong", "news", "stable", "A", "LIAR") is a concrete symbol.
-fn is_var(name: &str) -> bool {
-    name.len() == 1 && name.chars().next().unwrap_or(' ').is_ascii_lowercase()
+pub(crate) fn is_var(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_lowercase() => chars.all(|c| c.is_ascii_digit()),
+        _ => false,
+    }
 }
 
 /// Try to match `pattern` against `term`, filling `env` as we go.
 /// Returns true on successful match.
 ///
 /// Rules:
+///   • "_" matches anything and binds nothing.
 ///   • Variable (e.g. x) matches anything. If already bound, it must match the same Mu again.
 ///   • Concrete symbol matches only identical concrete symbol.
 ///   • Node([...]) matches Node([...]) elementwise.
 pub fn match_pattern(pattern: &Mu, term: &Mu, env: &mut Env) -> bool {
     match pattern {
+        // Anonymous wildcard: matches anything, binds nothing.
+        Mu::Sym(name) if name == "_" => true,
+
         // Variable case: single lowercase letter
         Mu::Sym(name) if is_var(name) => {
             if let Some(bound) = env.get(name) {
@@ -75,3 +86,158 @@ pub fn substitute_template(template: &Mu, env: &Env) -> Mu {
         ),
     }
 }
+
+/// Mints pattern-variable names for hygienic substitution. Every name
+/// `FreshVars` hands out carries a numeric suffix ("z0", "z1", ...), so it
+/// never equals a bare single-letter variable. `is_var` also accepts
+/// suffixed names, so a rule or value that itself uses a `z<digits>` name
+/// is not excluded from colliding with one minted here.
+#[derive(Debug, Clone, Default)]
+pub struct FreshVars(u64);
+
+impl FreshVars {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The next fresh variable name, e.g. "z0", "z1", "z2", ...
+    pub fn fresh_name(&mut self) -> String {
+        let name = format!("z{}", self.0);
+        self.0 += 1;
+        name
+    }
+}
+
+/// Collect every pattern-variable name free in `term` into `out`.
+fn free_vars(term: &Mu, out: &mut HashSet<String>) {
+    match term {
+        Mu::Sym(name) if is_var(name) => {
+            out.insert(name.clone());
+        }
+        Mu::Sym(_) => {}
+        Mu::Node(children) => children.iter().for_each(|c| free_vars(c, out)),
+    }
+}
+
+/// Rename every pattern-variable symbol in `term` per `renaming`. Symbols
+/// absent from `renaming` (concrete symbols, and variables not being
+/// renamed) pass through unchanged.
+pub(crate) fn alpha_rename(term: &Mu, renaming: &HashMap<String, String>) -> Mu {
+    match term {
+        Mu::Sym(name) if is_var(name) => match renaming.get(name) {
+            Some(fresh) => Mu::Sym(fresh.clone()),
+            None => term.clone(),
+        },
+        Mu::Sym(_) => term.clone(),
+        Mu::Node(children) => Mu::Node(children.iter().map(|c| alpha_rename(c, renaming)).collect()),
+    }
+}
+
+/// Capture-avoiding (hygienic) version of `substitute_template`.
+///
+/// A plain `substitute_template` call leaves any `template` variable that
+/// `env` doesn't bind as a literal leftover symbol (its "defensive
+/// default"). If a *bound* variable's value itself contains a free
+/// variable of the same name, the result has two occurrences of that name
+/// with unrelated origins - one a substituted-in value, the other a
+/// still-open template variable - that now look identical. Feed that term
+/// back through `match_pattern` (as repeated/nested rewriting does) and the
+/// open variable can be unintentionally bound to, or unified with, a
+/// subterm it never actually matched: the bound value has *captured* it.
+///
+/// This walks `template` and `env` first: any unbound template variable
+/// whose name collides with a variable free in some bound value is
+/// consistently renamed to a fresh name from `fresh` before the ordinary
+/// substitution runs, so no substituted-in value can capture a template
+/// binder it has no business capturing.
+pub fn substitute_template_hygienic(template: &Mu, env: &Env, fresh: &mut FreshVars) -> Mu {
+    let mut names_in_values = HashSet::new();
+    for value in env.values() {
+        free_vars(value, &mut names_in_values);
+    }
+
+    let mut template_vars = HashSet::new();
+    free_vars(template, &mut template_vars);
+
+    let renaming: HashMap<String, String> = template_vars
+        .into_iter()
+        .filter(|name| !env.contains_key(name) && names_in_values.contains(name))
+        .map(|name| (name, fresh.fresh_name()))
+        .collect();
+
+    substitute_template(&alpha_rename(template, &renaming), env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sym(s: &str) -> Mu {
+        Mu::Sym(s.to_string())
+    }
+
+    fn node(children: Vec<Mu>) -> Mu {
+        Mu::Node(children)
+    }
+
+    #[test]
+    fn is_var_accepts_a_freshened_name_but_not_a_concrete_multi_letter_symbol() {
+        assert!(is_var("x"));
+        assert!(is_var("x1"));
+        assert!(is_var("x23"));
+        assert!(!is_var("_"));
+        assert!(!is_var("X"));
+        assert!(!is_var("ping"));
+        assert!(!is_var(""));
+    }
+
+    #[test]
+    fn alpha_rename_only_touches_mapped_variables() {
+        let term = node(vec![sym("x"), sym("y"), sym("PING")]);
+        let renaming = HashMap::from([("x".to_string(), "z0".to_string())]);
+        assert_eq!(
+            alpha_rename(&term, &renaming),
+            node(vec![sym("z0"), sym("y"), sym("PING")])
+        );
+    }
+
+    #[test]
+    fn hygienic_substitution_matches_the_raw_version_when_nothing_collides() {
+        let template = node(vec![sym("x"), sym("y")]);
+        let env = Env::from([("x".to_string(), sym("A"))]);
+        let mut fresh = FreshVars::new();
+        assert_eq!(
+            substitute_template_hygienic(&template, &env, &mut fresh),
+            substitute_template(&template, &env)
+        );
+    }
+
+    #[test]
+    fn hygienic_substitution_renames_a_leftover_variable_captured_by_a_bound_value() {
+        // Template: [x, y] with only `x` bound, to a value that itself
+        // contains a free "y" - a naive substitution would leave the
+        // template's own open "y" looking identical to the "y" that just
+        // rode in inside x's value, even though they mean different things.
+        let template = node(vec![sym("x"), sym("y")]);
+        let env = Env::from([("x".to_string(), node(vec![sym("y")]))]);
+        let mut fresh = FreshVars::new();
+
+        let result = substitute_template_hygienic(&template, &env, &mut fresh);
+        let Mu::Node(children) = &result else {
+            panic!("expected a node");
+        };
+        let carried_in = &children[0]; // x's value, substituted as-is
+        let leftover = &children[1]; // template's own open variable, renamed
+
+        assert_eq!(carried_in, &node(vec![sym("y")]));
+        assert_ne!(leftover, &sym("y"), "the leftover template variable must not collide with the carried-in one");
+        assert!(matches!(leftover, Mu::Sym(name) if is_var(name)));
+    }
+
+    #[test]
+    fn fresh_vars_never_repeats() {
+        let mut fresh = FreshVars::new();
+        let names: HashSet<String> = (0..5).map(|_| fresh.fresh_name()).collect();
+        assert_eq!(names.len(), 5);
+    }
+}
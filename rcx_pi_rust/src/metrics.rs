@@ -0,0 +1,345 @@
+//! Cumulative regression-tracking metrics for engine/orbit runs.
+//!
+//! `engine_json::engine_run_to_json` and `orbit_json::orbit_to_json` each
+//! describe a single run. This module extracts a small numeric summary from
+//! that run - bucket sizes, orbit shape, rule-hit counts - and merges it
+//! into a persistent `metrics/metrics.json`, keyed by world name and a run
+//! label (a timestamp or git-sha, caller's choice), so a series of runs
+//! accumulates a history a CI job can diff between labels.
+//!
+//! Merging is additive and `serde`-free, in the same hand-rolled style as
+//! `orbit_json`/`engine_json`: read the existing file via `JsonValue`,
+//! insert the new `world -> label -> summary` entry, and rewrite it.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::json_value::JsonValue;
+use crate::orbit::OrbitStep;
+use crate::orbit_json::cycle_info;
+use crate::state::RCXState;
+use crate::types::Mu;
+
+/// Numeric summary of a single run, ready to be merged into `metrics.json`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunMetrics {
+    pub ra_count: usize,
+    pub lobes_count: usize,
+    pub sink_count: usize,
+    pub orbit_len: usize,
+    /// `Some((transient_len, period))` if the orbit closed a cycle before
+    /// terminating; `None` if it terminated (or didn't run long enough to
+    /// tell) instead.
+    pub cycle: Option<(usize, usize)>,
+    /// `program.rules` index -> number of times it fired, from provenance.
+    pub rule_hits: BTreeMap<usize, usize>,
+}
+
+impl RunMetrics {
+    /// Summarize a final engine state's buckets together with an orbit run's
+    /// shape and provenance. The two runs need not be the same run - a
+    /// caller with only one or the other can pass an empty/default state or
+    /// sequence and get zeroed-out fields for the side it skipped.
+    pub fn from_run(state: &RCXState, orbit_seq: &[Mu], orbit_prov: &[OrbitStep]) -> RunMetrics {
+        let mut rule_hits: BTreeMap<usize, usize> = BTreeMap::new();
+        for step in orbit_prov {
+            *rule_hits.entry(step.rule_i).or_insert(0) += 1;
+        }
+
+        RunMetrics {
+            ra_count: state.ra.len(),
+            lobes_count: state.lobes.len(),
+            sink_count: state.sink.len(),
+            orbit_len: orbit_seq.len(),
+            cycle: cycle_info(orbit_seq).and_then(|info| {
+                if info.terminated {
+                    None
+                } else {
+                    Some((info.transient_len, info.period))
+                }
+            }),
+            rule_hits,
+        }
+    }
+
+    fn to_json(&self) -> JsonValue {
+        let mut obj = BTreeMap::new();
+        obj.insert("ra_count".to_string(), JsonValue::Integer(self.ra_count as i128));
+        obj.insert(
+            "lobes_count".to_string(),
+            JsonValue::Integer(self.lobes_count as i128),
+        );
+        obj.insert(
+            "sink_count".to_string(),
+            JsonValue::Integer(self.sink_count as i128),
+        );
+        obj.insert(
+            "orbit_len".to_string(),
+            JsonValue::Integer(self.orbit_len as i128),
+        );
+
+        match self.cycle {
+            Some((transient_len, period)) => {
+                obj.insert(
+                    "transient_len".to_string(),
+                    JsonValue::Integer(transient_len as i128),
+                );
+                obj.insert("period".to_string(), JsonValue::Integer(period as i128));
+            }
+            None => {
+                obj.insert("transient_len".to_string(), JsonValue::Null);
+                obj.insert("period".to_string(), JsonValue::Null);
+            }
+        }
+
+        let rule_hits = self
+            .rule_hits
+            .iter()
+            .map(|(rule_i, hits)| (rule_i.to_string(), JsonValue::Integer(*hits as i128)))
+            .collect();
+        obj.insert("rule_hits".to_string(), JsonValue::Object(rule_hits));
+
+        JsonValue::Object(obj)
+    }
+
+    fn from_json(v: &JsonValue) -> Option<RunMetrics> {
+        let obj = match v {
+            JsonValue::Object(obj) => obj,
+            _ => return None,
+        };
+        let as_usize = |v: &JsonValue| -> Option<usize> {
+            match v {
+                JsonValue::Integer(i) => usize::try_from(*i).ok(),
+                _ => None,
+            }
+        };
+
+        let ra_count = as_usize(obj.get("ra_count")?)?;
+        let lobes_count = as_usize(obj.get("lobes_count")?)?;
+        let sink_count = as_usize(obj.get("sink_count")?)?;
+        let orbit_len = as_usize(obj.get("orbit_len")?)?;
+        let cycle = match (obj.get("transient_len"), obj.get("period")) {
+            (Some(t), Some(p)) => match (as_usize(t), as_usize(p)) {
+                (Some(t), Some(p)) => Some((t, p)),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        let mut rule_hits = BTreeMap::new();
+        if let Some(JsonValue::Object(hits)) = obj.get("rule_hits") {
+            for (rule_i, v) in hits {
+                if let (Ok(rule_i), Some(hits)) = (rule_i.parse::<usize>(), as_usize(v)) {
+                    rule_hits.insert(rule_i, hits);
+                }
+            }
+        }
+
+        Some(RunMetrics {
+            ra_count,
+            lobes_count,
+            sink_count,
+            orbit_len,
+            cycle,
+            rule_hits,
+        })
+    }
+}
+
+/// Read `path`, merge in `world`/`label` -> `metrics` (overwriting any prior
+/// entry under that same world+label), and rewrite `path`. Creates the file
+/// (and its summary) from scratch if `path` doesn't exist yet.
+pub fn merge_metrics(path: &Path, world: &str, label: &str, metrics: &RunMetrics) -> Result<(), String> {
+    let mut root = read_metrics_file(path)?;
+
+    let world_entry = root.entry(world.to_string()).or_default();
+    world_entry.insert(label.to_string(), metrics.to_json());
+
+    write_metrics_file(path, &root)
+}
+
+/// Look up a previously merged run's metrics by world + label.
+pub fn load_metrics(path: &Path, world: &str, label: &str) -> Result<Option<RunMetrics>, String> {
+    let root = read_metrics_file(path)?;
+    Ok(root
+        .get(world)
+        .and_then(|labels| labels.get(label))
+        .and_then(RunMetrics::from_json))
+}
+
+fn read_metrics_file(path: &Path) -> Result<BTreeMap<String, BTreeMap<String, JsonValue>>, String> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let src = std::fs::read_to_string(path).map_err(|e| format!("read {}: {e}", path.display()))?;
+    if src.trim().is_empty() {
+        return Ok(BTreeMap::new());
+    }
+    let value = JsonValue::parse(&src).map_err(|e| format!("parse {}: {e}", path.display()))?;
+
+    let worlds = match value {
+        JsonValue::Object(worlds) => worlds,
+        _ => return Err(format!("{}: expected a top-level JSON object", path.display())),
+    };
+
+    let mut out = BTreeMap::new();
+    for (world, labels) in worlds {
+        let labels = match labels {
+            JsonValue::Object(labels) => labels,
+            _ => return Err(format!("{}: world `{world}` is not an object", path.display())),
+        };
+        out.insert(world, labels);
+    }
+    Ok(out)
+}
+
+fn write_metrics_file(
+    path: &Path,
+    root: &BTreeMap<String, BTreeMap<String, JsonValue>>,
+) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("create {}: {e}", parent.display()))?;
+        }
+    }
+
+    let worlds = root
+        .iter()
+        .map(|(world, labels)| (world.clone(), JsonValue::Object(labels.clone())))
+        .collect();
+    let json = JsonValue::Object(worlds).to_canonical_json();
+
+    std::fs::write(path, json).map_err(|e| format!("write {}: {e}", path.display()))
+}
+
+/// One field that differs between two labels' metrics for the same world -
+/// the unit `metrics_diff` reports flagging a behavioral regression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricsChange {
+    pub field: String,
+    pub prev: String,
+    pub cur: String,
+}
+
+/// Compare two labels' metrics and flag any field that changed - most
+/// importantly `period`/`transient_len` (the orbit's shape) and the
+/// `ra`/`lobes`/`sink` bucket sizes, since those are exactly what a `.mu`
+/// world regression would move.
+pub fn metrics_diff(prev: &RunMetrics, cur: &RunMetrics) -> Vec<MetricsChange> {
+    let mut changes = Vec::new();
+
+    let mut push = |field: &str, prev: String, cur: String| {
+        if prev != cur {
+            changes.push(MetricsChange {
+                field: field.to_string(),
+                prev,
+                cur,
+            });
+        }
+    };
+
+    push("ra_count", prev.ra_count.to_string(), cur.ra_count.to_string());
+    push(
+        "lobes_count",
+        prev.lobes_count.to_string(),
+        cur.lobes_count.to_string(),
+    );
+    push("sink_count", prev.sink_count.to_string(), cur.sink_count.to_string());
+    push("orbit_len", prev.orbit_len.to_string(), cur.orbit_len.to_string());
+    push("cycle", format!("{:?}", prev.cycle), format!("{:?}", cur.cycle));
+
+    if prev.rule_hits != cur.rule_hits {
+        push(
+            "rule_hits",
+            format!("{:?}", prev.rule_hits),
+            format!("{:?}", cur.rule_hits),
+        );
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(ra: usize, period: usize) -> RunMetrics {
+        let mut rule_hits = BTreeMap::new();
+        rule_hits.insert(0, 3);
+        RunMetrics {
+            ra_count: ra,
+            lobes_count: 0,
+            sink_count: 0,
+            orbit_len: 4,
+            cycle: Some((0, period)),
+            rule_hits,
+        }
+    }
+
+    fn temp_path(tag: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "rcx_metrics_test_{tag}_{}.json",
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn merge_then_load_round_trips() {
+        let path = temp_path("roundtrip");
+        let _ = std::fs::remove_file(&path);
+
+        let metrics = sample(1, 2);
+        merge_metrics(&path, "pingpong", "2026-01-01", &metrics).unwrap();
+
+        let loaded = load_metrics(&path, "pingpong", "2026-01-01").unwrap();
+        assert_eq!(loaded, Some(metrics));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn merge_is_additive_across_labels_and_worlds() {
+        let path = temp_path("additive");
+        let _ = std::fs::remove_file(&path);
+
+        merge_metrics(&path, "pingpong", "run1", &sample(1, 2)).unwrap();
+        merge_metrics(&path, "pingpong", "run2", &sample(1, 3)).unwrap();
+        merge_metrics(&path, "rcx_core", "run1", &sample(2, 2)).unwrap();
+
+        assert_eq!(
+            load_metrics(&path, "pingpong", "run1").unwrap(),
+            Some(sample(1, 2))
+        );
+        assert_eq!(
+            load_metrics(&path, "pingpong", "run2").unwrap(),
+            Some(sample(1, 3))
+        );
+        assert_eq!(
+            load_metrics(&path, "rcx_core", "run1").unwrap(),
+            Some(sample(2, 2))
+        );
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn diff_flags_period_change_but_not_identical_runs() {
+        let a = sample(1, 2);
+        let b = sample(1, 2);
+        assert!(metrics_diff(&a, &b).is_empty());
+
+        let c = sample(1, 3);
+        let changes = metrics_diff(&a, &c);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].field, "cycle");
+    }
+
+    #[test]
+    fn diff_flags_bucket_distribution_change() {
+        let a = sample(1, 2);
+        let b = sample(2, 2);
+        let changes = metrics_diff(&a, &b);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].field, "ra_count");
+    }
+}
@@ -0,0 +1,200 @@
+//! Canonical binary codec for Mu, in the spirit of the Preserves canonical
+//! binary format: a deterministic tag-length-value encoding with no optional
+//! whitespace and fixed child ordering, so structurally equal `Mu` values
+//! always produce identical bytes.
+//!
+//! Wire format:
+//!   - `Sym(s)`:  `0x01` ++ LEB128(len(s)) ++ utf8(s)
+//!   - `Node(cs)`: `0x02` ++ LEB128(len(cs)) ++ encode(cs[0]) ++ encode(cs[1]) ++ ...
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::types::Mu;
+
+const TAG_SYM: u8 = 0x01;
+const TAG_NODE: u8 = 0x02;
+
+/// Encode a Mu term into its canonical byte representation.
+pub fn encode(mu: &Mu) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode_into(mu, &mut out);
+    out
+}
+
+fn encode_into(mu: &Mu, out: &mut Vec<u8>) {
+    match mu {
+        Mu::Sym(s) => {
+            out.push(TAG_SYM);
+            write_varint(s.len() as u64, out);
+            out.extend_from_slice(s.as_bytes());
+        }
+        Mu::Node(children) => {
+            out.push(TAG_NODE);
+            write_varint(children.len() as u64, out);
+            for c in children {
+                encode_into(c, out);
+            }
+        }
+    }
+}
+
+fn write_varint(mut value: u64, out: &mut Vec<u8>) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            break;
+        } else {
+            out.push(byte | 0x80);
+        }
+    }
+}
+
+fn read_varint(bytes: &[u8]) -> Result<(u64, usize), String> {
+    let mut value: u64 = 0;
+    let mut shift: u32 = 0;
+    let mut consumed = 0;
+
+    loop {
+        let byte = *bytes
+            .get(consumed)
+            .ok_or_else(|| "truncated varint".to_string())?;
+        consumed += 1;
+
+        value |= ((byte & 0x7f) as u64) << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok((value, consumed));
+        }
+
+        shift += 7;
+        if shift >= 64 {
+            return Err("varint too long".to_string());
+        }
+    }
+}
+
+/// Decode a single Mu term from the front of `bytes`.
+/// Returns the decoded term and the number of bytes consumed.
+pub fn decode(bytes: &[u8]) -> Result<(Mu, usize), String> {
+    let tag = *bytes.first().ok_or_else(|| "truncated input: no tag byte".to_string())?;
+    let mut pos = 1;
+
+    match tag {
+        TAG_SYM => {
+            let (len, n) = read_varint(&bytes[pos..])?;
+            pos += n;
+            let len = len as usize;
+            let end = pos
+                .checked_add(len)
+                .ok_or_else(|| "symbol length overflow".to_string())?;
+            let payload = bytes
+                .get(pos..end)
+                .ok_or_else(|| "truncated symbol payload".to_string())?;
+            let s = std::str::from_utf8(payload)
+                .map_err(|e| format!("invalid utf-8 symbol payload: {e}"))?;
+            Ok((Mu::Sym(s.to_string()), end))
+        }
+        TAG_NODE => {
+            let (count, n) = read_varint(&bytes[pos..])?;
+            pos += n;
+            // Each child needs at least 2 bytes (a tag byte plus a
+            // one-byte-minimum varint), so a `count` that couldn't
+            // possibly fit in what's left of `bytes` is malformed input,
+            // not a giant-but-honest node - reject it before trusting it
+            // as a `Vec::with_capacity` size.
+            let max_possible_children = (bytes.len() - pos) as u64 / 2;
+            if count > max_possible_children {
+                return Err("node child count exceeds remaining input".to_string());
+            }
+            let mut children = Vec::with_capacity(count as usize);
+            for _ in 0..count {
+                let (child, used) = decode(&bytes[pos..])?;
+                children.push(child);
+                pos += used;
+            }
+            Ok((Mu::Node(children), pos))
+        }
+        other => Err(format!("unknown tag byte: 0x{other:02x}")),
+    }
+}
+
+/// Decode a single Mu term, rejecting any trailing bytes.
+/// Use [`decode`] directly when decoding a stream of concatenated terms.
+pub fn decode_one(bytes: &[u8]) -> Result<Mu, String> {
+    let (mu, used) = decode(bytes)?;
+    if used != bytes.len() {
+        return Err(format!(
+            "trailing garbage after decoded term: {} extra byte(s)",
+            bytes.len() - used
+        ));
+    }
+    Ok(mu)
+}
+
+/// Hash a Mu term over its canonical byte encoding, so structurally equal
+/// terms always hash identically (consistent with the derived `Hash` impl).
+pub fn mu_hash(mu: &Mu) -> u64 {
+    let bytes = encode(mu);
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_flat_node() {
+        let mu = Mu::Node(vec![Mu::Sym("A".to_string()), Mu::Sym("B".to_string())]);
+        let bytes = encode(&mu);
+        assert_eq!(decode_one(&bytes).unwrap(), mu);
+    }
+
+    #[test]
+    fn round_trips_nested_node() {
+        let mu = Mu::Node(vec![
+            Mu::Sym("omega".to_string()),
+            Mu::Node(vec![Mu::Sym("a".to_string()), Mu::Sym("b".to_string())]),
+        ]);
+        let bytes = encode(&mu);
+        assert_eq!(decode_one(&bytes).unwrap(), mu);
+    }
+
+    #[test]
+    fn equal_terms_encode_identically_and_hash_the_same() {
+        let a = Mu::Node(vec![Mu::Sym("x".to_string())]);
+        let b = Mu::Node(vec![Mu::Sym("x".to_string())]);
+        assert_eq!(encode(&a), encode(&b));
+        assert_eq!(mu_hash(&a), mu_hash(&b));
+    }
+
+    #[test]
+    fn rejects_trailing_garbage() {
+        let mu = Mu::Sym("A".to_string());
+        let mut bytes = encode(&mu);
+        bytes.push(0xff);
+        assert!(decode_one(&bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_varint() {
+        let bytes = vec![TAG_SYM, 0x80]; // continuation bit set, but no more bytes
+        assert!(decode(&bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_a_node_whose_child_count_cannot_fit_in_what_remains() {
+        // A node tag claiming a ~u64::MAX child count, with no bytes left
+        // to back it - must error, not panic trying to pre-size a Vec.
+        let bytes = [
+            &[TAG_NODE][..],
+            &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01][..],
+        ]
+        .concat();
+        assert!(decode(&bytes).is_err());
+    }
+}
@@ -1,11 +1,224 @@
+use std::fmt;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Write};
+use std::io::Write;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 
 use crate::formatter::mu_to_string;
 use crate::parser::parse_mu;
 use crate::types::{Mu, RcxProgram, RcxRule, RuleAction};
 
+/// Why a `.mu` rule line failed to parse in `load_mu_file`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MuLoadErrorKind {
+    /// No `->` found on the line at all.
+    MissingArrow,
+    /// More than one top-level `->` on the line (ambiguous split point).
+    MultipleArrows,
+    /// The left-hand side didn't parse as a `Mu` term; holds `parse_mu`'s message.
+    BadPattern(String),
+    /// The right-hand side wasn't `ra`/`lobe`/`sink`/`rewrite(...)`/`unify(...)`; holds the offending text.
+    UnknownAction(String),
+    /// `rewrite(...)` or `unify(...)` was missing its parens, or its payload didn't parse as a `Mu` term.
+    MalformedRewrite(String),
+    /// The file couldn't be opened or read at all; holds the underlying `io::Error`'s message.
+    Io(String),
+}
+
+/// A `load_mu_file` failure, carrying enough position information to render
+/// an annotated source snippet - the offending line, a caret/underline
+/// under the exact span, and the message - in the style of a compiler
+/// diagnostic, rather than a flat "parse foo.mu: ..." string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MuLoadError {
+    pub path: String,
+    /// 1-based line number; 0 when the failure isn't tied to a specific
+    /// line (e.g. the file couldn't be opened).
+    pub line: usize,
+    /// Byte offset of `span.start` within the (trimmed) source line.
+    pub col: usize,
+    /// Byte range of the offending token within `source_line`.
+    pub span: Range<usize>,
+    /// The full (trimmed) source line the span is relative to; empty when
+    /// `line` is 0.
+    pub source_line: String,
+    pub kind: MuLoadErrorKind,
+}
+
+impl MuLoadError {
+    fn new(path: &str, line: usize, source_line: &str, span: Range<usize>, kind: MuLoadErrorKind) -> Self {
+        Self {
+            path: path.to_string(),
+            line,
+            col: span.start,
+            span,
+            source_line: source_line.to_string(),
+            kind,
+        }
+    }
+
+    fn io(path: &str, message: String) -> Self {
+        Self {
+            path: path.to_string(),
+            line: 0,
+            col: 0,
+            span: 0..0,
+            source_line: String::new(),
+            kind: MuLoadErrorKind::Io(message),
+        }
+    }
+
+    fn message(&self) -> String {
+        match &self.kind {
+            MuLoadErrorKind::MissingArrow => "expected `lhs -> rhs`".to_string(),
+            MuLoadErrorKind::MultipleArrows => "rule line has more than one `->`".to_string(),
+            MuLoadErrorKind::BadPattern(e) => format!("bad pattern: {e}"),
+            MuLoadErrorKind::UnknownAction(target) => {
+                format!("unknown target `{target}` (expected ra|lobe|sink|rewrite(...)|unify(...))")
+            }
+            MuLoadErrorKind::MalformedRewrite(e) => e.clone(),
+            MuLoadErrorKind::Io(e) => e.clone(),
+        }
+    }
+
+    /// Render an annotated snippet in the style of compiler diagnostics:
+    /// `path:line:col: message`, the source line, and a caret/underline
+    /// under the exact offending span.
+    pub fn render(&self) -> String {
+        if self.source_line.is_empty() {
+            return format!("{}: {}", self.path, self.message());
+        }
+        let underline = "^".repeat(self.span.len().max(1));
+        format!(
+            "{}:{}:{}: {}\n  {}\n  {}{}",
+            self.path,
+            self.line,
+            self.col + 1,
+            self.message(),
+            self.source_line,
+            " ".repeat(self.col),
+            underline
+        )
+    }
+}
+
+impl fmt::Display for MuLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render())
+    }
+}
+
+impl std::error::Error for MuLoadError {}
+
+/// Lets existing `Result<_, String>` call sites keep working against `?`.
+impl From<MuLoadError> for String {
+    fn from(e: MuLoadError) -> Self {
+        e.to_string()
+    }
+}
+
+/// A cursor over one `.mu` rule line, advanced one combinator at a time and
+/// tracking its absolute byte position so a scanned term's bounds can be
+/// turned straight into a `MuLoadError` span.
+///
+/// This replaces scanning the line with `str::find("->")`/`split_once('(')`,
+/// which (like `rule_dsl`'s old `split("->")`) breaks the moment a rewrite
+/// target itself contains a literal `->` inside brackets or a quoted symbol.
+struct Cursor<'a> {
+    rest: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(line: &'a str) -> Self {
+        Cursor { rest: line, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest.chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.rest = &self.rest[c.len_utf8()..];
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    /// Whitespace combinator: consume zero or more spaces/tabs.
+    fn ws(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+            self.bump();
+        }
+    }
+
+    /// Literal-tag combinator: consume `tag` if the input starts with it.
+    fn lit(&mut self, tag: &str) -> bool {
+        if self.rest.starts_with(tag) {
+            self.rest = &self.rest[tag.len()..];
+            self.pos += tag.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Term combinator: scan a Mu term's source text (a pattern, or an
+    /// action's target), respecting `[`/`]` nesting and `"..."` quoting
+    /// (with `\`-escapes), stopping at the first top-level `->`, `#`, or end
+    /// of input. Returns the trimmed text and its byte span within the line.
+    fn term(&mut self) -> Result<(String, Range<usize>), String> {
+        let start = self.pos;
+        let mut depth: i32 = 0;
+        let mut end = self.rest.len();
+        let mut chars = self.rest.char_indices();
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '[' => depth += 1,
+                ']' => {
+                    depth -= 1;
+                    if depth < 0 {
+                        return Err(format!("unbalanced `]` in `{}`", self.rest));
+                    }
+                }
+                '"' => loop {
+                    match chars.next() {
+                        None => return Err(format!("unterminated quoted symbol in `{}`", self.rest)),
+                        Some((_, '\\')) => {
+                            chars.next();
+                        }
+                        Some((_, '"')) => break,
+                        Some(_) => {}
+                    }
+                },
+                '#' if depth == 0 => {
+                    end = i;
+                    break;
+                }
+                '-' if depth == 0 && self.rest[i..].starts_with("->") => {
+                    end = i;
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        if depth != 0 {
+            return Err(format!("unbalanced `[` in `{}`", self.rest));
+        }
+
+        let (text, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        self.pos = start + end;
+
+        let trimmed = text.trim();
+        let leading_ws = text.len() - text.trim_start().len();
+        let span_start = start + leading_ws;
+        Ok((trimmed.to_string(), span_start..span_start + trimmed.len()))
+    }
+}
+
 /// Normalize a world filename into a concrete path under `mu_programs/`.
 ///
 /// Accepts things like:
@@ -38,7 +251,7 @@ fn normalize_world_path(name: &str) -> PathBuf {
 ///   [paradox,_] -> sink
 ///   PING        -> rewrite(PONG)
 ///   [PING,PING] -> rewrite([PONG,PING])
-pub fn load_mu_file(name: &str) -> Result<RcxProgram, String> {
+pub fn load_mu_file(name: &str) -> Result<RcxProgram, MuLoadError> {
     let path = {
         let p = Path::new(name);
         if p.exists() {
@@ -47,14 +260,28 @@ pub fn load_mu_file(name: &str) -> Result<RcxProgram, String> {
             normalize_world_path(name)
         }
     };
+    let path_str = path.display().to_string();
 
-    let file = File::open(&path).map_err(|e| format!("open {}: {e}", path.display()))?;
-    let reader = BufReader::new(file);
+    let src = std::fs::read_to_string(&path)
+        .map_err(|e| MuLoadError::io(&path_str, format!("read: {e}")))?;
 
+    parse_program_at(&path_str, &src)
+}
+
+/// Parse a whole `.mu` program from in-memory source, e.g. a world built at
+/// runtime rather than loaded from `mu_programs/`.
+pub fn parse_program(src: &str) -> Result<RcxProgram, MuLoadError> {
+    parse_program_at("<in-memory>", src)
+}
+
+/// Shared implementation behind `load_mu_file` and `parse_program`: split
+/// `src` into lines, skip blanks and whole-line `#` comments, and parse the
+/// rest as rule lines. `path_str` is only used to label diagnostics.
+fn parse_program_at(path_str: &str, src: &str) -> Result<RcxProgram, MuLoadError> {
     let mut rules: Vec<RcxRule> = Vec::new();
 
-    for line_res in reader.lines() {
-        let raw = line_res.map_err(|e| format!("read {}: {e}", path.display()))?;
+    for (line_no, raw) in src.lines().enumerate() {
+        let line_no = line_no + 1;
         let line = raw.trim();
 
         // skip empty / comment lines
@@ -62,60 +289,154 @@ pub fn load_mu_file(name: &str) -> Result<RcxProgram, String> {
             continue;
         }
 
-        let parts: Vec<&str> = line.split("->").collect();
-        if parts.len() != 2 {
-            return Err(format!(
-                "parse {}: expected `lhs -> rhs`, got `{}`",
-                path.display(),
-                line
-            ));
-        }
+        rules.push(parse_rule_line(path_str, line_no, line)?);
+    }
 
-        let pattern_src = parts[0].trim();
-        let target_src = parts[1].trim();
-
-        // left side is always a Mu pattern
-        let pattern: Mu = parse_mu(pattern_src)
-            .map_err(|e| format!("parse pattern in {}: {e}", path.display()))?;
-
-        // right side can be:
-        //   ra | lobe | sink | rewrite(<Mu>)
-        let action: RuleAction = if target_src.eq_ignore_ascii_case("ra") {
-            RuleAction::ToRa
-        } else if target_src.eq_ignore_ascii_case("lobe") {
-            RuleAction::ToLobe
-        } else if target_src.eq_ignore_ascii_case("sink") {
-            RuleAction::ToSink
-        } else if target_src.to_lowercase().starts_with("rewrite") {
-            // Expect rewrite(<Mu>)
-            let maybe_arg = target_src
-                .split_once('(')
-                .and_then(|(_, rest)| rest.strip_suffix(')'));
-
-            let arg_src = maybe_arg.ok_or_else(|| {
-                format!(
-                    "parse {}: expected `rewrite(<Mu>)`, got `{}`",
-                    path.display(),
-                    target_src
-                )
-            })?;
-
-            let mu = parse_mu(arg_src.trim())
-                .map_err(|e| format!("parse rewrite payload in {}: {e}", path.display()))?;
-
-            RuleAction::Rewrite(mu)
-        } else {
-            return Err(format!(
-                "parse {}: unknown target `{}` (expected ra|lobe|sink|rewrite(...))",
-                path.display(),
-                target_src
-            ));
-        };
+    Ok(RcxProgram { rules })
+}
 
-        rules.push(RcxRule { pattern, action });
+/// Parse one non-blank, non-comment `.mu` rule line, given its 1-based line
+/// number, into an `RcxRule` - or a `MuLoadError` pinpointing exactly which
+/// byte span of the line was at fault.
+///
+/// Surface syntax: `<pattern> -> ra|lobe|sink|rewrite(<Mu>)|unify(<Mu>) [# comment]`.
+/// Unlike the `rule_dsl` DSL used by snapshots, there is no guard clause,
+/// and `rewrite`/`unify` always take their payload in parens.
+fn parse_rule_line(path_str: &str, line_no: usize, line: &str) -> Result<RcxRule, MuLoadError> {
+    let mut cursor = Cursor::new(line);
+    cursor.ws();
+
+    let (pattern_src, pattern_span) = cursor.term().map_err(|e| {
+        MuLoadError::new(path_str, line_no, line, 0..line.len(), MuLoadErrorKind::BadPattern(e))
+    })?;
+
+    cursor.ws();
+    if !cursor.lit("->") {
+        return Err(MuLoadError::new(
+            path_str,
+            line_no,
+            line,
+            0..line.len(),
+            MuLoadErrorKind::MissingArrow,
+        ));
     }
+    cursor.ws();
 
-    Ok(RcxProgram { rules })
+    let (target_src, target_span) = cursor.term().map_err(|e| {
+        MuLoadError::new(path_str, line_no, line, 0..line.len(), MuLoadErrorKind::MalformedRewrite(e))
+    })?;
+
+    cursor.ws();
+    if cursor.lit("->") {
+        return Err(MuLoadError::new(
+            path_str,
+            line_no,
+            line,
+            0..line.len(),
+            MuLoadErrorKind::MultipleArrows,
+        ));
+    }
+
+    // left side is always a Mu pattern
+    let pattern: Mu = parse_mu(&pattern_src).map_err(|e| {
+        MuLoadError::new(
+            path_str,
+            line_no,
+            line,
+            pattern_span,
+            MuLoadErrorKind::BadPattern(e.to_string()),
+        )
+    })?;
+
+    // right side can be:
+    //   ra | lobe | sink | rewrite(<Mu>) | unify(<Mu>)
+    let action: RuleAction = if target_src.eq_ignore_ascii_case("ra") {
+        RuleAction::ToRa
+    } else if target_src.eq_ignore_ascii_case("lobe") {
+        RuleAction::ToLobe
+    } else if target_src.eq_ignore_ascii_case("sink") {
+        RuleAction::ToSink
+    } else if target_src.to_lowercase().starts_with("rewrite") {
+        // Expect rewrite(<Mu>)
+        let maybe_arg = target_src
+            .split_once('(')
+            .and_then(|(_, rest)| rest.strip_suffix(')'));
+
+        let arg_src = maybe_arg.ok_or_else(|| {
+            MuLoadError::new(
+                path_str,
+                line_no,
+                line,
+                target_span.clone(),
+                MuLoadErrorKind::MalformedRewrite(format!(
+                    "expected `rewrite(<Mu>)`, got `{target_src}`"
+                )),
+            )
+        })?;
+
+        let mu = parse_mu(arg_src.trim()).map_err(|e| {
+            MuLoadError::new(
+                path_str,
+                line_no,
+                line,
+                target_span.clone(),
+                MuLoadErrorKind::MalformedRewrite(format!("parse rewrite payload: {e}")),
+            )
+        })?;
+
+        RuleAction::Rewrite(mu)
+    } else if target_src.to_lowercase().starts_with("unify") {
+        // Expect unify(<Mu>)
+        let maybe_arg = target_src
+            .split_once('(')
+            .and_then(|(_, rest)| rest.strip_suffix(')'));
+
+        let arg_src = maybe_arg.ok_or_else(|| {
+            MuLoadError::new(
+                path_str,
+                line_no,
+                line,
+                target_span.clone(),
+                MuLoadErrorKind::MalformedRewrite(format!(
+                    "expected `unify(<Mu>)`, got `{target_src}`"
+                )),
+            )
+        })?;
+
+        let mu = parse_mu(arg_src.trim()).map_err(|e| {
+            MuLoadError::new(
+                path_str,
+                line_no,
+                line,
+                target_span.clone(),
+                MuLoadErrorKind::MalformedRewrite(format!("parse unify payload: {e}")),
+            )
+        })?;
+
+        RuleAction::RewriteTemplate(mu)
+    } else {
+        return Err(MuLoadError::new(
+            path_str,
+            line_no,
+            line,
+            target_span,
+            MuLoadErrorKind::UnknownAction(target_src),
+        ));
+    };
+
+    cursor.ws();
+    let comment = if cursor.lit("#") {
+        Some(cursor.rest.trim().to_string())
+    } else {
+        None
+    };
+
+    Ok(RcxRule {
+        pattern,
+        guard: None,
+        action,
+        comment,
+    })
 }
 
 /// Save the current program into `mu_programs/NAME.mu`.
@@ -145,6 +466,7 @@ pub fn save_mu_file(name: &str, program: &RcxProgram) -> Result<String, String>
             RuleAction::ToLobe => "lobe".to_string(),
             RuleAction::ToSink => "sink".to_string(),
             RuleAction::Rewrite(mu) => format!("rewrite({})", mu_to_string(mu)),
+            RuleAction::RewriteTemplate(mu) => format!("unify({})", mu_to_string(mu)),
         };
 
         writeln!(file, "{} -> {}", lhs, rhs)
@@ -153,3 +475,97 @@ pub fn save_mu_file(name: &str, program: &RcxProgram) -> Result<String, String>
 
     Ok(fname)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_arrow_underlines_whole_line() {
+        let err = parse_rule_line("w.mu", 3, "not a rule").unwrap_err();
+        assert_eq!(err.kind, MuLoadErrorKind::MissingArrow);
+        assert_eq!(err.line, 3);
+        assert_eq!(err.span, 0..10);
+    }
+
+    #[test]
+    fn unknown_action_underlines_just_the_target() {
+        let err = parse_rule_line("w.mu", 1, "[omega,_] -> unknownverb").unwrap_err();
+        assert_eq!(
+            err.kind,
+            MuLoadErrorKind::UnknownAction("unknownverb".to_string())
+        );
+        let start = err.span.start;
+        assert_eq!(&"[omega,_] -> unknownverb"[err.span.clone()], "unknownverb");
+        assert_eq!(err.col, start);
+    }
+
+    #[test]
+    fn render_points_a_caret_under_the_span() {
+        let err = parse_rule_line("w.mu", 1, "[omega,_] -> unknownverb").unwrap_err();
+        let rendered = err.render();
+        assert!(rendered.contains("w.mu:1:14:"));
+        let caret_line = rendered.lines().last().unwrap();
+        assert!(caret_line.trim_end().ends_with(&"^".repeat("unknownverb".len())));
+    }
+
+    #[test]
+    fn malformed_rewrite_missing_parens_is_reported() {
+        let err = parse_rule_line("w.mu", 1, "PING -> rewrite PONG").unwrap_err();
+        assert!(matches!(err.kind, MuLoadErrorKind::MalformedRewrite(_)));
+    }
+
+    #[test]
+    fn multiple_arrows_is_reported() {
+        let err = parse_rule_line("w.mu", 1, "A -> ra -> lobe").unwrap_err();
+        assert_eq!(err.kind, MuLoadErrorKind::MultipleArrows);
+    }
+
+    #[test]
+    fn valid_rule_line_parses() {
+        let rule = parse_rule_line("w.mu", 1, "PING -> rewrite(PONG)").unwrap();
+        assert_eq!(
+            rule.action,
+            RuleAction::Rewrite(Mu::Sym("PONG".to_string()))
+        );
+    }
+
+    #[test]
+    fn display_falls_back_to_render() {
+        let err = parse_rule_line("w.mu", 1, "not a rule").unwrap_err();
+        assert_eq!(err.to_string(), err.render());
+    }
+
+    #[test]
+    fn arrow_inside_quoted_rewrite_payload_is_not_mistaken_for_the_arrow() {
+        let rule = parse_rule_line("w.mu", 1, r#"PING -> rewrite("a->b")"#).unwrap();
+        assert_eq!(rule.action, RuleAction::Rewrite(Mu::Sym("a->b".to_string())));
+    }
+
+    #[test]
+    fn trailing_comment_is_captured() {
+        let rule = parse_rule_line("w.mu", 1, "PING -> rewrite(PONG) # ping back").unwrap();
+        assert_eq!(rule.comment.as_deref(), Some("ping back"));
+    }
+
+    #[test]
+    fn parse_program_parses_multiple_lines_and_skips_blanks_and_comments() {
+        let program = parse_program(
+            "# a little world\n\n[null,_] -> ra\nPING -> rewrite(PONG)\n",
+        )
+        .unwrap();
+        assert_eq!(program.rules.len(), 2);
+        assert!(matches!(program.rules[0].action, RuleAction::ToRa));
+        assert_eq!(
+            program.rules[1].action,
+            RuleAction::Rewrite(Mu::Sym("PONG".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_program_reports_the_right_line_number() {
+        let err = parse_program("PING -> rewrite(PONG)\nnot a rule\n").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert_eq!(err.kind, MuLoadErrorKind::MissingArrow);
+    }
+}
@@ -0,0 +1,267 @@
+//! Preserves-style path query language for selecting Mu subterms.
+//!
+//! A path is an ordered list of [`Step`]s applied left-to-right, where each
+//! step maps the current set of matched values to a new set. This gives a
+//! way to query/inspect `state.ra`/`lobes`/`sink` buckets and trace payloads
+//! without writing manual recursion.
+
+use crate::types::Mu;
+
+/// One step of a path query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    /// All direct children of every `Node` in the current set.
+    Children,
+    /// The i-th child of every `Node` in the current set.
+    Nth(usize),
+    /// Every transitive subterm (including self) of every value in the
+    /// current set, visited pre-order.
+    Descendants,
+    /// Keep only values satisfying `Predicate`.
+    Filter(Predicate),
+}
+
+/// A predicate tested against a single `Mu` value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Predicate {
+    IsSym,
+    IsNode,
+    /// `Node` with exactly `n` children.
+    Arity(usize),
+    /// `Node` whose first child is `Sym(s)`.
+    HeadIs(String),
+    /// Structurally equal to the given `Mu`.
+    Eq(Mu),
+}
+
+impl Predicate {
+    fn test(&self, value: &Mu) -> bool {
+        match self {
+            Predicate::IsSym => matches!(value, Mu::Sym(_)),
+            Predicate::IsNode => matches!(value, Mu::Node(_)),
+            Predicate::Arity(n) => matches!(value, Mu::Node(children) if children.len() == *n),
+            Predicate::HeadIs(s) => matches!(
+                value,
+                Mu::Node(children) if matches!(children.first(), Some(Mu::Sym(h)) if h == s)
+            ),
+            Predicate::Eq(expected) => value == expected,
+        }
+    }
+}
+
+/// A path: an ordered sequence of steps.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Path {
+    steps: Vec<Step>,
+}
+
+impl Path {
+    /// Start building an empty path (matches only the root).
+    pub fn new() -> Self {
+        Self { steps: Vec::new() }
+    }
+
+    pub fn children(mut self) -> Self {
+        self.steps.push(Step::Children);
+        self
+    }
+
+    pub fn nth(mut self, i: usize) -> Self {
+        self.steps.push(Step::Nth(i));
+        self
+    }
+
+    pub fn descendants(mut self) -> Self {
+        self.steps.push(Step::Descendants);
+        self
+    }
+
+    pub fn filter(mut self, pred: Predicate) -> Self {
+        self.steps.push(Step::Filter(pred));
+        self
+    }
+
+    /// Evaluate this path against `root`, returning every matching subterm.
+    pub fn eval<'a>(&self, root: &'a Mu) -> Vec<&'a Mu> {
+        let mut current: Vec<&'a Mu> = vec![root];
+
+        for step in &self.steps {
+            current = match step {
+                Step::Children => current
+                    .into_iter()
+                    .flat_map(|v| match v {
+                        Mu::Node(children) => children.iter().collect::<Vec<_>>(),
+                        Mu::Sym(_) => Vec::new(),
+                    })
+                    .collect(),
+
+                Step::Nth(i) => current
+                    .into_iter()
+                    .filter_map(|v| match v {
+                        Mu::Node(children) => children.get(*i),
+                        Mu::Sym(_) => None,
+                    })
+                    .collect(),
+
+                Step::Descendants => current
+                    .into_iter()
+                    .flat_map(|v| {
+                        let mut out = Vec::new();
+                        collect_descendants(v, &mut out);
+                        out
+                    })
+                    .collect(),
+
+                Step::Filter(pred) => current.into_iter().filter(|v| pred.test(v)).collect(),
+            };
+        }
+
+        current
+    }
+}
+
+fn collect_descendants<'a>(mu: &'a Mu, out: &mut Vec<&'a Mu>) {
+    out.push(mu);
+    if let Mu::Node(children) = mu {
+        for c in children {
+            collect_descendants(c, out);
+        }
+    }
+}
+
+/// Convenience: evaluate a textual path against `root`.
+pub fn query<'a>(selector: &str, root: &'a Mu) -> Result<Vec<&'a Mu>, String> {
+    let path = parse_path(selector)?;
+    Ok(path.eval(root))
+}
+
+/// Parse the tiny textual selector syntax into a [`Path`].
+///
+/// Supported segments, separated by `/`:
+///   - `*`            → `Children`
+///   - `**`           → `Descendants`
+///   - `[n]`          → `Nth(n)`
+///   - `:SYM`         → `HeadIs(SYM)` (filter)
+///   - `:HEAD=SYM`    → `HeadIs(SYM)` (filter)
+///   - `:ARITY=n`     → `Arity(n)` (filter)
+///   - `:SYM?`        → `IsSym` (filter)
+///   - `:NODE?`       → `IsNode` (filter)
+///
+/// e.g. `/*/[0]` (children, then their first child) or
+/// `//:HEAD=MULT_MARKER` (every descendant headed by `MULT_MARKER`).
+pub fn parse_path(selector: &str) -> Result<Path, String> {
+    let mut path = Path::new();
+    let mut rest = selector.trim();
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("//") {
+            path = path.descendants();
+            rest = after;
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix('/') {
+            rest = after;
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix("**") {
+            path = path.descendants();
+            rest = after;
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix('*') {
+            path = path.children();
+            rest = after;
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix('[') {
+            let end = after
+                .find(']')
+                .ok_or_else(|| format!("unterminated `[` in selector `{selector}`"))?;
+            let n: usize = after[..end]
+                .parse()
+                .map_err(|_| format!("expected integer index in `[{}]`", &after[..end]))?;
+            path = path.nth(n);
+            rest = &after[end + 1..];
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix(':') {
+            let end = after
+                .find('/')
+                .unwrap_or(after.len());
+            let seg = &after[..end];
+            let pred = if seg == "SYM?" {
+                Predicate::IsSym
+            } else if seg == "NODE?" {
+                Predicate::IsNode
+            } else if let Some(n) = seg.strip_prefix("ARITY=") {
+                Predicate::Arity(
+                    n.parse()
+                        .map_err(|_| format!("expected integer in `:ARITY={n}`"))?,
+                )
+            } else if let Some(s) = seg.strip_prefix("HEAD=") {
+                Predicate::HeadIs(s.to_string())
+            } else {
+                Predicate::HeadIs(seg.to_string())
+            };
+            path = path.filter(pred);
+            rest = &after[end..];
+            continue;
+        }
+
+        return Err(format!(
+            "unrecognized selector segment at `{}` in `{selector}`",
+            rest
+        ));
+    }
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Mu {
+        Mu::Node(vec![
+            Mu::Sym("MULT_MARKER".to_string()),
+            Mu::Node(vec![Mu::Sym("a".to_string()), Mu::Sym("b".to_string())]),
+            Mu::Sym("c".to_string()),
+        ])
+    }
+
+    #[test]
+    fn children_step_returns_direct_children() {
+        let root = sample();
+        let path = Path::new().children();
+        assert_eq!(path.eval(&root).len(), 3);
+    }
+
+    #[test]
+    fn nth_step_selects_one_child() {
+        let root = sample();
+        let path = Path::new().nth(0);
+        assert_eq!(path.eval(&root), vec![&Mu::Sym("MULT_MARKER".to_string())]);
+    }
+
+    #[test]
+    fn descendants_includes_self() {
+        let root = sample();
+        let path = Path::new().descendants();
+        // self + 3 children + 2 grandchildren = 6
+        assert_eq!(path.eval(&root).len(), 6);
+    }
+
+    #[test]
+    fn filter_head_is_matches_only_marked_nodes() {
+        let root = sample();
+        let matches = query("//:HEAD=MULT_MARKER", &root).unwrap();
+        assert_eq!(matches, vec![&root]);
+    }
+
+    #[test]
+    fn textual_nth_of_children() {
+        let root = sample();
+        let matches = query("/*/[0]", &root).unwrap();
+        assert_eq!(matches, vec![&Mu::Sym("a".to_string())]);
+    }
+}
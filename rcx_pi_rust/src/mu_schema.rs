@@ -0,0 +1,234 @@
+//! Schema definitions that validate Mu shapes, in the spirit of Preserves
+//! Schema: a map of named productions describing the legal shapes a world
+//! accepts, checked before a term enters `process_input` or gets persisted.
+
+use std::collections::HashMap;
+
+use crate::types::{Mu, RcxProgram, RuleAction};
+
+/// One production in a schema.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Production {
+    /// Must equal a specific symbol.
+    AtomLit(String),
+    /// Matches any symbol.
+    AnySym,
+    /// A `Node` of exactly this fixed arity, each child matched positionally.
+    Seq(Vec<Production>),
+    /// Matches if any alternative matches.
+    Alt(Vec<Production>),
+    /// Recursive reference to another named production.
+    Ref(String),
+}
+
+/// A schema: a map of named productions.
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    pub productions: HashMap<String, Production>,
+}
+
+impl Schema {
+    pub fn new() -> Self {
+        Self {
+            productions: HashMap::new(),
+        }
+    }
+
+    pub fn with(mut self, name: impl Into<String>, production: Production) -> Self {
+        self.productions.insert(name.into(), production);
+        self
+    }
+}
+
+/// A schema validation failure: the path to the offending subterm (a
+/// sequence of child indices from the root) plus what was expected/found.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaError {
+    pub path: Vec<usize>,
+    pub expected: String,
+    pub found: String,
+}
+
+impl std::fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "schema mismatch at {:?}: expected {}, found {}",
+            self.path, self.expected, self.found
+        )
+    }
+}
+
+fn describe(mu: &Mu) -> String {
+    match mu {
+        Mu::Sym(s) => format!("Sym(\"{s}\")"),
+        Mu::Node(children) => format!("Node(arity = {})", children.len()),
+    }
+}
+
+fn describe_production(production: &Production) -> String {
+    match production {
+        Production::AtomLit(s) => format!("atom `{s}`"),
+        Production::AnySym => "any symbol".to_string(),
+        Production::Seq(parts) => format!("a node of arity {}", parts.len()),
+        Production::Alt(alts) => format!(
+            "one of [{}]",
+            alts.iter()
+                .map(describe_production)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Production::Ref(name) => format!("production `{name}`"),
+    }
+}
+
+/// Walk `value` against the named `root` production in `schema`.
+pub fn validate(schema: &Schema, root: &str, value: &Mu) -> Result<(), SchemaError> {
+    let production = schema
+        .productions
+        .get(root)
+        .ok_or_else(|| SchemaError {
+            path: Vec::new(),
+            expected: format!("known production `{root}`"),
+            found: "undefined production".to_string(),
+        })?;
+    validate_production(schema, production, value, &mut Vec::new())
+}
+
+fn validate_production(
+    schema: &Schema,
+    production: &Production,
+    value: &Mu,
+    path: &mut Vec<usize>,
+) -> Result<(), SchemaError> {
+    match production {
+        Production::AtomLit(expected) => match value {
+            Mu::Sym(s) if s == expected => Ok(()),
+            other => Err(SchemaError {
+                path: path.clone(),
+                expected: format!("atom `{expected}`"),
+                found: describe(other),
+            }),
+        },
+
+        Production::AnySym => match value {
+            Mu::Sym(_) => Ok(()),
+            other => Err(SchemaError {
+                path: path.clone(),
+                expected: "any symbol".to_string(),
+                found: describe(other),
+            }),
+        },
+
+        Production::Seq(parts) => match value {
+            Mu::Node(children) if children.len() == parts.len() => {
+                for (i, (part, child)) in parts.iter().zip(children.iter()).enumerate() {
+                    path.push(i);
+                    validate_production(schema, part, child, path)?;
+                    path.pop();
+                }
+                Ok(())
+            }
+            other => Err(SchemaError {
+                path: path.clone(),
+                expected: format!("a node of arity {}", parts.len()),
+                found: describe(other),
+            }),
+        },
+
+        Production::Alt(alts) => {
+            for alt in alts {
+                if validate_production(schema, alt, value, path).is_ok() {
+                    return Ok(());
+                }
+            }
+            Err(SchemaError {
+                path: path.clone(),
+                expected: describe_production(production),
+                found: describe(value),
+            })
+        }
+
+        Production::Ref(name) => {
+            let referenced = schema.productions.get(name).ok_or_else(|| SchemaError {
+                path: path.clone(),
+                expected: format!("known production `{name}`"),
+                found: "undefined production".to_string(),
+            })?;
+            validate_production(schema, referenced, value, path)
+        }
+    }
+}
+
+impl RcxProgram {
+    /// Check every rule pattern (and every `Rewrite` RHS) against `schema`'s
+    /// named `root` production, so malformed `.mu` worlds are rejected at
+    /// load time instead of misrouting silently.
+    pub fn validate_against(&self, schema: &Schema, root: &str) -> Result<(), SchemaError> {
+        for rule in &self.rules {
+            validate(schema, root, &rule.pattern)?;
+            if let RuleAction::Rewrite(rhs) = &rule.action {
+                validate(schema, root, rhs)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::RcxRule;
+
+    fn pair_schema() -> Schema {
+        Schema::new().with(
+            "pair",
+            Production::Seq(vec![
+                Production::AtomLit("PAIR".to_string()),
+                Production::AnySym,
+                Production::AnySym,
+            ]),
+        )
+    }
+
+    #[test]
+    fn validates_matching_shape() {
+        let schema = pair_schema();
+        let value = Mu::Node(vec![
+            Mu::Sym("PAIR".to_string()),
+            Mu::Sym("a".to_string()),
+            Mu::Sym("b".to_string()),
+        ]);
+        assert!(validate(&schema, "pair", &value).is_ok());
+    }
+
+    #[test]
+    fn reports_path_to_offending_subterm() {
+        let schema = pair_schema();
+        let value = Mu::Node(vec![
+            Mu::Sym("PAIR".to_string()),
+            Mu::Node(vec![Mu::Sym("nested".to_string())]),
+            Mu::Sym("b".to_string()),
+        ]);
+        let err = validate(&schema, "pair", &value).unwrap_err();
+        assert_eq!(err.path, vec![1]);
+    }
+
+    #[test]
+    fn validate_against_checks_rules_and_rewrite_rhs() {
+        let schema = pair_schema();
+        let program = RcxProgram::new(vec![RcxRule::new(
+            Mu::Node(vec![
+                Mu::Sym("PAIR".to_string()),
+                Mu::Sym("x".to_string()),
+                Mu::Sym("y".to_string()),
+            ]),
+            RuleAction::Rewrite(Mu::Node(vec![
+                Mu::Sym("PAIR".to_string()),
+                Mu::Sym("y".to_string()),
+                Mu::Sym("x".to_string()),
+            ])),
+        )]);
+        assert!(program.validate_against(&schema, "pair").is_ok());
+    }
+}
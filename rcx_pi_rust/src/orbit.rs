@@ -19,7 +19,7 @@ pub fn orbit(program: &RcxProgram, seed: Mu, max_steps: usize) -> Vec<Mu> {
     seq.push(current.clone());
 
     for _ in 0..max_steps {
-        if let Some(next) = step_once(program, &current) {
+        if let Some(next) = step(program, &current) {
             current = next;
             seq.push(current.clone());
         } else {
@@ -30,16 +30,342 @@ pub fn orbit(program: &RcxProgram, seed: Mu, max_steps: usize) -> Vec<Mu> {
     seq
 }
 
-fn step_once(program: &RcxProgram, current: &Mu) -> Option<Mu> {
-    for RcxRule { pattern, action } in &program.rules {
+/// Apply the first matching `Rewrite` rule to `current`, or `None` if no
+/// rule matches (the orbit has terminated). The step function `classify`
+/// and `classify_cycle` drive to classify orbits without materializing them.
+pub fn step(program: &RcxProgram, current: &Mu) -> Option<Mu> {
+    for RcxRule { pattern, action, .. } in &program.rules {
+        if let RuleAction::Rewrite(template) = action {
+            let mut env: Env = Env::new();
+            if !match_pattern(pattern, current, &mut env) {
+                continue;
+            }
+            return Some(substitute_template(template, &env));
+        }
+    }
+    None
+}
+
+/// Like `step`, but also returns which rule fired and the bindings it
+/// matched under - the raw material `orbit_with_provenance` records per step.
+/// Only this provenance-tracking path pays for cloning `pattern`/`template`.
+fn step_once_with_rule(program: &RcxProgram, current: &Mu) -> Option<(Mu, usize, Mu, Mu, Env)> {
+    for (rule_i, RcxRule { pattern, action, .. }) in program.rules.iter().enumerate() {
         if let RuleAction::Rewrite(template) = action {
             let mut env: Env = Env::new();
             if !match_pattern(pattern, current, &mut env) {
                 continue;
             }
             let rewritten = substitute_template(template, &env);
-            return Some(rewritten);
+            return Some((rewritten, rule_i, pattern.clone(), template.clone(), env));
         }
     }
     None
 }
+
+/// One rewrite step's provenance: which rule fired, under which bindings,
+/// to get from `seq[i - 1]` to `seq[i]`.
+pub struct OrbitStep {
+    /// Index into the orbit sequence of the state this step produced.
+    pub i: usize,
+    /// Index into `program.rules` of the rule that fired.
+    pub rule_i: usize,
+    pub pattern: Mu,
+    pub template: Mu,
+    /// Captured pattern-variable bindings, sorted by name for determinism.
+    pub bindings: Vec<(String, Mu)>,
+}
+
+/// Like `orbit`, but also returns the provenance of every step: which rule
+/// fired and under which bindings. Used by `orbit_json::orbit_to_json` for
+/// its `provenance` field and by `metrics` for rule-hit counts.
+pub fn orbit_with_provenance(
+    program: &RcxProgram,
+    seed: Mu,
+    max_steps: usize,
+) -> (Vec<Mu>, Vec<OrbitStep>) {
+    let mut seq: Vec<Mu> = Vec::new();
+    let mut prov: Vec<OrbitStep> = Vec::new();
+    let mut current = seed.clone();
+
+    seq.push(current.clone());
+
+    for _ in 0..max_steps {
+        match step_once_with_rule(program, &current) {
+            Some((next, rule_i, pattern, template, env)) => {
+                current = next;
+                seq.push(current.clone());
+
+                let mut bindings: Vec<(String, Mu)> = env.into_iter().collect();
+                bindings.sort_by(|a, b| a.0.cmp(&b.0));
+
+                prov.push(OrbitStep {
+                    i: seq.len() - 1,
+                    rule_i,
+                    pattern,
+                    template,
+                    bindings,
+                });
+            }
+            None => break,
+        }
+    }
+
+    (seq, prov)
+}
+
+/// The shape of an orbit, as found by `classify_cycle` in constant memory
+/// rather than by materializing the whole sequence and scanning it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CycleInfo {
+    /// Steps from the seed to the first state that recurs (`mu`, in Brent's
+    /// naming). Meaningless (reported as the orbit's depth) when `terminated`.
+    pub transient_len: usize,
+    /// The cycle's period (`lam`). `0` when `terminated`.
+    pub period: usize,
+    /// `true` if the step function returned `None` (no rule matched) before
+    /// a cycle closed, rather than the orbit looping back on itself.
+    pub terminated: bool,
+}
+
+/// Brent's cycle-detection algorithm: find the orbit's transient length and
+/// period in O(1) memory and O(mu + lam) calls to `f`, instead of
+/// materializing the whole sequence and scanning it for a repeat.
+///
+/// `f` must be a pure function of its argument (same input always yields
+/// the same output) since the transient-length phase replays `f` from the
+/// seed a second time. `None` from `f` before a cycle closes means the
+/// orbit terminated; `None` from `classify_cycle` itself means `step_cap`
+/// was exhausted with neither a cycle nor a termination found.
+pub fn classify_cycle<F>(seed: &Mu, step_cap: usize, mut f: F) -> Option<CycleInfo>
+where
+    F: FnMut(&Mu) -> Option<Mu>,
+{
+    // Phase 1 (Brent): find the period `lam`. Once the saved `tortoise`
+    // sits on the cycle, the hare meets it again after exactly one period,
+    // so `lam` is the true period rather than a multiple of it.
+    let mut power: usize = 1;
+    let mut lam: usize = 1;
+    let mut tortoise = seed.clone();
+
+    let mut hare = match f(seed) {
+        Some(h) => h,
+        None => {
+            return Some(CycleInfo {
+                transient_len: 1,
+                period: 0,
+                terminated: true,
+            });
+        }
+    };
+    let mut steps: usize = 1;
+
+    while tortoise != hare {
+        if steps >= step_cap {
+            return None;
+        }
+
+        if power == lam {
+            tortoise = hare.clone();
+            power *= 2;
+            lam = 0;
+        }
+
+        hare = match f(&hare) {
+            Some(h) => h,
+            None => {
+                return Some(CycleInfo {
+                    transient_len: steps + 1,
+                    period: 0,
+                    terminated: true,
+                });
+            }
+        };
+        lam += 1;
+        steps += 1;
+    }
+
+    // Phase 2: find the true transient length `mu` by walking a `hare`
+    // that starts `lam` steps ahead of a `tortoise` reset to the seed -
+    // they meet exactly at the first state that recurs.
+    let mut tortoise = seed.clone();
+    let mut hare = seed.clone();
+    for _ in 0..lam {
+        hare = match f(&hare) {
+            Some(h) => h,
+            None => {
+                return Some(CycleInfo {
+                    transient_len: lam,
+                    period: lam,
+                    terminated: true,
+                });
+            }
+        };
+    }
+
+    let mut mu = 0usize;
+    while tortoise != hare {
+        tortoise = match f(&tortoise) {
+            Some(t) => t,
+            None => {
+                return Some(CycleInfo {
+                    transient_len: mu,
+                    period: lam,
+                    terminated: true,
+                });
+            }
+        };
+        hare = match f(&hare) {
+            Some(h) => h,
+            None => {
+                return Some(CycleInfo {
+                    transient_len: mu,
+                    period: lam,
+                    terminated: true,
+                });
+            }
+        };
+        mu += 1;
+    }
+
+    Some(CycleInfo {
+        transient_len: mu,
+        period: lam,
+        terminated: false,
+    })
+}
+
+/// A structured ω-limit classification, driven by `classify_cycle` in
+/// constant memory. The `Display` impl renders the same text `classify`
+/// has always returned, so callers that only want a message can keep using
+/// that; callers that want to count/group/batch-summarize classifications
+/// (like `orbit_batch`) get a value they can match on instead of scraping a
+/// string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrbitClass {
+    FixedPoint,
+    LimitCycle { period: usize },
+    Transient { len: usize, then: Box<OrbitClass> },
+    /// The step cap was exhausted before a cycle closed, or the orbit
+    /// terminated outright (no rule matched) before one did; either way,
+    /// `steps` is how far we got.
+    Unresolved { steps: usize },
+}
+
+impl std::fmt::Display for OrbitClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrbitClass::FixedPoint => write!(f, "fixed point"),
+            OrbitClass::LimitCycle { period } => write!(f, "pure limit cycle (period = {period})"),
+            OrbitClass::Transient { len, then } => match then.as_ref() {
+                OrbitClass::FixedPoint => write!(f, "transient of length {len} then fixed point"),
+                OrbitClass::LimitCycle { period } => {
+                    write!(f, "transient of length {len} then limit cycle (period = {period})")
+                }
+                other => write!(f, "transient of length {len} then {other}"),
+            },
+            OrbitClass::Unresolved { steps } => write!(f, "no detected cycle up to {steps} steps"),
+        }
+    }
+}
+
+/// Classify an orbit into a structured `OrbitClass`, driving `classify_cycle`
+/// instead of materializing the orbit - the unified home for what used to be
+/// separate, duplicated classifiers in `orbit_json` and `examples/orbit_cli.rs`.
+pub fn classify_structured<F>(seed: &Mu, step_cap: usize, f: F) -> OrbitClass
+where
+    F: FnMut(&Mu) -> Option<Mu>,
+{
+    match classify_cycle(seed, step_cap, f) {
+        None => OrbitClass::Unresolved {
+            steps: step_cap + 1,
+        },
+        Some(CycleInfo {
+            terminated: true,
+            transient_len,
+            ..
+        }) => OrbitClass::Unresolved {
+            steps: transient_len,
+        },
+        Some(CycleInfo {
+            transient_len: 0,
+            period: 1,
+            ..
+        }) => OrbitClass::FixedPoint,
+        Some(CycleInfo {
+            transient_len: 0,
+            period,
+            ..
+        }) => OrbitClass::LimitCycle { period },
+        Some(CycleInfo {
+            transient_len,
+            period: 1,
+            ..
+        }) => OrbitClass::Transient {
+            len: transient_len,
+            then: Box::new(OrbitClass::FixedPoint),
+        },
+        Some(CycleInfo {
+            transient_len,
+            period,
+            ..
+        }) => OrbitClass::Transient {
+            len: transient_len,
+            then: Box::new(OrbitClass::LimitCycle { period }),
+        },
+    }
+}
+
+/// Classify an orbit into a human-readable ω-limit description. A thin
+/// `Display` wrapper around `classify_structured` kept for callers (the
+/// golden-vector harness, `orbit_json`, `orbit_cli`) that only want the
+/// message.
+pub fn classify<F>(seed: &Mu, step_cap: usize, f: F) -> String
+where
+    F: FnMut(&Mu) -> Option<Mu>,
+{
+    classify_structured(seed, step_cap, f).to_string()
+}
+
+/// Classify a whole batch of seeds under the same read-only `program`,
+/// fanning the (embarrassingly parallel - each seed's orbit is independent)
+/// work across worker threads. Returns one `(seed, class)` pair per input
+/// seed, in the same order `seeds` was given.
+pub fn orbit_batch(program: &RcxProgram, seeds: &[Mu], max_steps: usize) -> Vec<(Mu, OrbitClass)> {
+    if seeds.is_empty() {
+        return Vec::new();
+    }
+
+    let worker_count = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(seeds.len());
+    let chunk_size = seeds.len().div_ceil(worker_count).max(1);
+    let program = std::sync::Arc::new(program.clone());
+
+    let mut results = Vec::with_capacity(seeds.len());
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = seeds
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let program = std::sync::Arc::clone(&program);
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|seed| {
+                            let class =
+                                classify_structured(seed, max_steps, |current| step(&program, current));
+                            (seed.clone(), class)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            results.extend(handle.join().expect("orbit_batch worker thread panicked"));
+        }
+    });
+
+    results
+}
@@ -1,68 +1,46 @@
+use crate::orbit::{self, orbit_with_provenance, CycleInfo};
 use crate::formatter::mu_to_string;
-use crate::orbit::orbit_with_provenance;
 use crate::schemas::ORBIT_SCHEMA_V1;
 use crate::types::{Mu, RcxProgram};
 
-/// Classify an orbit sequence into a simple ω-limit description.
-/// Mirrors the logic used in examples/orbit_cli.rs and examples/repl.rs.
-fn classify_orbit(seq: &[Mu]) -> String {
-    if seq.is_empty() {
-        return "empty orbit (no states produced)".to_string();
-    }
-    if seq.len() == 1 {
-        return "no detected cycle up to 1 steps".to_string();
-    }
-
-    // 1) Try "pure cycle from the seed" detection.
-    let seed = &seq[0];
-    let mut found_period: Option<usize> = None;
-
-    for i in 1..seq.len() {
-        if &seq[i] == seed {
-            found_period = Some(i);
-            break;
-        }
-    }
-
-    if let Some(period) = found_period {
-        let mut pure = true;
-        for (idx, mu) in seq.iter().enumerate() {
-            if mu != &seq[idx % period] {
-                pure = false;
-                break;
-            }
-        }
-
-        if pure {
-            if period == 1 {
-                return "fixed point".to_string();
-            } else {
-                return format!("pure limit cycle (period = {period})");
-            }
-        }
+/// Replay an already-materialized orbit sequence through `orbit::classify`'s
+/// Brent's-algorithm core instead of a fresh rewrite-step closure - lets a
+/// caller that already holds the full `seq` (this module's `orbit_to_json`,
+/// `metrics`) reuse the exact same unified classifier that `harness` and
+/// `examples/orbit_cli.rs` drive straight off `orbit::step` without ever
+/// materializing a sequence.
+///
+/// `classify_cycle` calls this with whatever state its tortoise/hare walk is
+/// currently at - including replaying from the seed a second time in its
+/// transient-length phase - so it must look each state up by value rather
+/// than assuming calls arrive in the same order `seq` was built in.
+fn replay(seq: &[Mu]) -> impl Fn(&Mu) -> Option<Mu> + '_ {
+    move |current: &Mu| {
+        seq.iter()
+            .position(|m| m == current)
+            .and_then(|i| seq.get(i + 1).cloned())
     }
+}
 
-    // 2) Fallback: transient + cycle detection using last state.
-    let last = &seq[seq.len() - 1];
-
-    if let Some(prev_idx) = seq[..seq.len() - 1].iter().rposition(|m| m == last) {
-        let transient_len = prev_idx;
-        let period = seq.len() - 1 - prev_idx;
+/// Numeric cycle info for an already-materialized orbit sequence.
+///
+/// `pub(crate)` so `metrics` can pull the same fields `classify_orbit`
+/// formats into prose, instead of re-parsing its text.
+pub(crate) fn cycle_info(seq: &[Mu]) -> Option<CycleInfo> {
+    let seed = seq.first()?;
+    orbit::classify_cycle(seed, seq.len(), replay(seq))
+}
 
-        if period == 1 {
-            if transient_len == 0 {
-                "fixed point".to_string()
-            } else {
-                format!("transient of length {transient_len} then fixed point")
-            }
-        } else if transient_len == 0 {
-            format!("pure limit cycle (period = {period})")
-        } else {
-            format!("transient of length {transient_len} then limit cycle (period = {period})")
-        }
-    } else {
-        format!("no detected cycle up to {} steps", seq.len())
-    }
+/// Classify an orbit sequence into a simple ω-limit description.
+///
+/// Used by `orbit_to_json`; `harness`'s `OrbitClassify` mode classifies
+/// through `orbit::classify` directly instead.
+pub(crate) fn classify_orbit(seq: &[Mu]) -> String {
+    let seed = match seq.first() {
+        Some(seed) => seed,
+        None => return "empty orbit (no states produced)".to_string(),
+    };
+    orbit::classify(seed, seq.len(), replay(seq))
 }
 
 /// Produce a JSON string describing an orbit run.
@@ -1,39 +1,356 @@
+use std::fmt;
+
 use crate::types::Mu;
 
-/// Very small Mu parser:
-/// - `A`          → Sym("A")
-/// - `[A,A]`      → Node([Sym("A"), Sym("A")])
-/// - `[NEWS,STABLE]` → Node([Sym("NEWS"), Sym("STABLE")])
+/// A `parse_mu` failure, carrying the byte span of the offending token so a
+/// caller can render a compiler-style caret-underlined diagnostic against
+/// the original input, in the spirit of `mu_loader::MuLoadError`.
 ///
-/// No nesting yet, just flat lists of symbols.
-pub fn parse_mu(input: &str) -> Result<Mu, String> {
-    let s = input.trim();
-
-    // List form: [A,B,C]
-    if s.starts_with('[') && s.ends_with(']') {
-        let inner = &s[1..s.len() - 1]; // strip [ and ]
-        if inner.trim().is_empty() {
-            return Err("empty list [] is not supported yet".to_string());
+/// `Display` renders the same `"<message> at byte <offset>"` text the old
+/// `Result<Mu, String>` call sites already expected, so existing
+/// `format!("...: {e}")` call sites keep compiling unchanged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    pub offset: usize,
+    pub len: usize,
+}
+
+impl ParseError {
+    fn new(message: impl Into<String>, offset: usize, len: usize) -> Self {
+        ParseError {
+            message: message.into(),
+            offset,
+            len,
         }
+    }
 
-        let parts: Vec<&str> = inner.split(',').collect();
-        let mut children = Vec::with_capacity(parts.len());
+    /// Render a three-line compiler-style diagnostic: `input` as-is, a
+    /// caret span (`^` repeated `len.max(1)` times) under byte `offset`,
+    /// then the message.
+    pub fn render(&self, input: &str) -> String {
+        format!(
+            "{}\n{}{}\n{}",
+            input,
+            " ".repeat(self.offset),
+            "^".repeat(self.len.max(1)),
+            self.message
+        )
+    }
+}
 
-        for raw in parts {
-            let sym = raw.trim();
-            if sym.is_empty() {
-                return Err(format!("empty symbol in list: `{input}`"));
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Lets existing `Result<_, String>` call sites keep working against `?`.
+impl From<ParseError> for String {
+    fn from(e: ParseError) -> Self {
+        e.to_string()
+    }
+}
+
+/// A single lexical token produced by `tokenize`, along with the byte offset
+/// and length (into the original input) of its source span.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LBrack,
+    RBrack,
+    Comma,
+    Sym(String),
+    QuotedSym(String),
+}
+
+/// Tokenize a Mu source string.
+///
+/// - `[` / `]` / `,` are structural tokens.
+/// - Whitespace is skipped between tokens.
+/// - A double-quoted run (`"..."`) becomes a `QuotedSym`, and may itself
+///   contain commas, brackets, or spaces; `\"` and `\\` are recognized escapes.
+/// - Anything else is read up to the next structural character or whitespace
+///   and becomes a bare `Sym` (this is how `_` falls out as a first-class atom).
+fn tokenize(input: &str) -> Result<Vec<(Token, usize, usize)>, ParseError> {
+    let bytes = input.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i] as char;
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        match c {
+            '[' => {
+                tokens.push((Token::LBrack, i, 1));
+                i += 1;
+            }
+            ']' => {
+                tokens.push((Token::RBrack, i, 1));
+                i += 1;
+            }
+            ',' => {
+                tokens.push((Token::Comma, i, 1));
+                i += 1;
+            }
+            '"' => {
+                let start = i;
+                i += 1;
+                let mut sym = String::new();
+                loop {
+                    if i >= bytes.len() {
+                        return Err(ParseError::new(
+                            "unterminated quoted symbol",
+                            start,
+                            i - start,
+                        ));
+                    }
+                    let ch = bytes[i] as char;
+                    if ch == '"' {
+                        i += 1;
+                        break;
+                    }
+                    if ch == '\\' && i + 1 < bytes.len() {
+                        let next = bytes[i + 1] as char;
+                        match next {
+                            '"' | '\\' => {
+                                sym.push(next);
+                                i += 2;
+                                continue;
+                            }
+                            _ => {}
+                        }
+                    }
+                    sym.push(ch);
+                    i += 1;
+                }
+                tokens.push((Token::QuotedSym(sym), start, i - start));
+            }
+            _ => {
+                let start = i;
+                while i < bytes.len() {
+                    let ch = bytes[i] as char;
+                    if ch.is_whitespace() || matches!(ch, '[' | ']' | ',' | '"') {
+                        break;
+                    }
+                    i += 1;
+                }
+                let sym = &input[start..i];
+                if sym.is_empty() {
+                    return Err(ParseError::new(
+                        format!("unexpected character `{c}`"),
+                        start,
+                        1,
+                    ));
+                }
+                tokens.push((Token::Sym(sym.to_string()), start, i - start));
             }
-            // For now we treat every token as a plain symbol.
-            children.push(Mu::Sym(sym.to_string()));
         }
+    }
 
-        Ok(Mu::Node(children))
-    } else {
-        // Atom form: "A", "NEWS", "q", etc.
-        if s.is_empty() {
-            return Err("empty input".to_string());
+    Ok(tokens)
+}
+
+/// Recursive-descent parser over the token stream.
+struct Parser<'a> {
+    tokens: &'a [(Token, usize, usize)],
+    pos: usize,
+    input_len: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&(Token, usize, usize)> {
+        self.tokens.get(self.pos)
+    }
+
+    fn bump(&mut self) -> Option<&(Token, usize, usize)> {
+        let tok = self.tokens.get(self.pos);
+        self.pos += 1;
+        tok
+    }
+
+    fn parse_mu(&mut self) -> Result<Mu, ParseError> {
+        match self.bump() {
+            Some((Token::Sym(s), _, _)) => Ok(Mu::Sym(s.clone())),
+            Some((Token::QuotedSym(s), _, _)) => Ok(Mu::Sym(s.clone())),
+            Some((Token::LBrack, _, _)) => self.parse_node(),
+            Some((Token::RBrack, off, len)) => {
+                Err(ParseError::new("unexpected `]`", *off, *len))
+            }
+            Some((Token::Comma, off, len)) => {
+                Err(ParseError::new("unexpected `,`", *off, *len))
+            }
+            None => Err(ParseError::new("unexpected end of input", self.input_len, 0)),
         }
-        Ok(Mu::Sym(s.to_string()))
+    }
+
+    /// Called just after consuming the opening `[`.
+    fn parse_node(&mut self) -> Result<Mu, ParseError> {
+        let mut children = Vec::new();
+
+        // Empty list: `[]`
+        if let Some((Token::RBrack, _, _)) = self.peek() {
+            self.bump();
+            return Ok(Mu::Node(children));
+        }
+
+        loop {
+            let child = self.parse_mu()?;
+            children.push(child);
+
+            match self.bump() {
+                Some((Token::Comma, _, _)) => continue,
+                Some((Token::RBrack, _, _)) => break,
+                Some((_, off, len)) => {
+                    return Err(ParseError::new("expected `,` or `]`", *off, *len));
+                }
+                None => {
+                    return Err(ParseError::new(
+                        "unterminated list: expected `,` or `]`",
+                        self.input_len,
+                        0,
+                    ));
+                }
+            }
+        }
+
+        Ok(Mu::Node(children))
+    }
+}
+
+/// Parse a Mu term from its textual form.
+///
+/// - `A`              → `Sym("A")`
+/// - `_`               → `Sym("_")` (the wildcard atom)
+/// - `"a, b"`          → `Sym("a, b")` (quoted symbols may contain commas/brackets/spaces)
+/// - `[A,B]`           → `Node([Sym("A"), Sym("B")])`
+/// - `[omega,[a,b]]`   → arbitrarily nested `Node`s
+///
+/// On failure, the returned `ParseError` carries the byte offset (and, where
+/// known, length) of the token that caused the parser to fail, so a caller
+/// can either just print it (`Display` renders `"<message> at byte N"`) or
+/// call `ParseError::render` for a caret-underlined diagnostic.
+pub fn parse_mu(input: &str) -> Result<Mu, ParseError> {
+    let trimmed = input.trim_start();
+    let leading_ws = input.len() - trimmed.len();
+
+    if trimmed.trim_end().is_empty() {
+        return Err(ParseError::new("empty input", leading_ws, 0));
+    }
+
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+        return Err(ParseError::new("empty input", leading_ws, 0));
+    }
+
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+        input_len: input.len(),
+    };
+
+    let mu = parser.parse_mu()?;
+
+    if parser.pos != parser.tokens.len() {
+        let (_, off, len) = &parser.tokens[parser.pos];
+        return Err(ParseError::new(
+            "trailing input after complete term",
+            *off,
+            *len,
+        ));
+    }
+
+    Ok(mu)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_flat_atom() {
+        assert_eq!(parse_mu("A").unwrap(), Mu::Sym("A".to_string()));
+    }
+
+    #[test]
+    fn parses_wildcard_atom() {
+        assert_eq!(parse_mu("_").unwrap(), Mu::Sym("_".to_string()));
+    }
+
+    #[test]
+    fn parses_flat_list() {
+        assert_eq!(
+            parse_mu("[A,B]").unwrap(),
+            Mu::Node(vec![Mu::Sym("A".to_string()), Mu::Sym("B".to_string())])
+        );
+    }
+
+    #[test]
+    fn parses_nested_list() {
+        assert_eq!(
+            parse_mu("[omega,[a,b]]").unwrap(),
+            Mu::Node(vec![
+                Mu::Sym("omega".to_string()),
+                Mu::Node(vec![Mu::Sym("a".to_string()), Mu::Sym("b".to_string())]),
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_quoted_symbol_with_comma() {
+        assert_eq!(
+            parse_mu(r#"["a, b",c]"#).unwrap(),
+            Mu::Node(vec![
+                Mu::Sym("a, b".to_string()),
+                Mu::Sym("c".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_whitespace_between_tokens() {
+        assert_eq!(
+            parse_mu("[ A , [ B , C ] ]").unwrap(),
+            Mu::Node(vec![
+                Mu::Sym("A".to_string()),
+                Mu::Node(vec![Mu::Sym("B".to_string()), Mu::Sym("C".to_string())]),
+            ])
+        );
+    }
+
+    #[test]
+    fn reports_byte_offset_on_unbalanced_brackets() {
+        let err = parse_mu("[A,B").unwrap_err();
+        assert!(err.to_string().contains("byte"));
+    }
+
+    #[test]
+    fn rejects_empty_input() {
+        assert!(parse_mu("").is_err());
+        assert!(parse_mu("   ").is_err());
+    }
+
+    #[test]
+    fn error_offset_and_len_point_at_the_offending_token() {
+        let err = parse_mu("[A,]").unwrap_err();
+        assert_eq!(err.offset, 3);
+        assert_eq!(err.len, 1);
+    }
+
+    #[test]
+    fn render_draws_a_caret_under_the_offending_span() {
+        let input = "[A,]";
+        let err = parse_mu(input).unwrap_err();
+        let rendered = err.render(input);
+        let mut lines = rendered.lines();
+        assert_eq!(lines.next(), Some(input));
+        assert_eq!(lines.next(), Some("   ^"));
+        assert_eq!(lines.next(), Some(err.message.as_str()));
     }
 }
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use crate::types::Mu;
 
 /// Simple structural pattern matching for Mu.
@@ -26,3 +28,112 @@ pub fn mu_matches(pattern: &Mu, value: &Mu) -> bool {
         _ => false,
     }
 }
+
+/// Is this symbol a capture variable, e.g. `?x`?
+fn is_capture(name: &str) -> bool {
+    name.starts_with('?') && name.len() > 1
+}
+
+/// Structural pattern matching with named-variable capture.
+///
+/// Conventions:
+///   - Sym("_") is an anonymous wildcard: matches anything, binds nothing.
+///   - Sym("?x") is a capture variable: binds `x -> value` in `env` the first
+///     time it's seen; on a later occurrence, succeeds only if `value` is
+///     structurally equal (`==`) to the already-bound term (non-linear match).
+///   - Sym("foo") matches only the symbol "foo".
+///   - Node([...]) must match shape and recursively match children.
+pub fn mu_match_bind(pattern: &Mu, value: &Mu, env: &mut HashMap<String, Mu>) -> bool {
+    match pattern {
+        Mu::Sym(p) if p == "_" => true,
+
+        Mu::Sym(p) if is_capture(p) => {
+            let name = &p[1..];
+            if let Some(bound) = env.get(name) {
+                bound == value
+            } else {
+                env.insert(name.to_string(), value.clone());
+                true
+            }
+        }
+
+        Mu::Sym(p) => matches!(value, Mu::Sym(v) if p == v),
+
+        Mu::Node(ps) => match value {
+            Mu::Node(vs) => {
+                if ps.len() != vs.len() {
+                    return false;
+                }
+                ps.iter()
+                    .zip(vs.iter())
+                    .all(|(pp, vv)| mu_match_bind(pp, vv, env))
+            }
+            _ => false,
+        },
+    }
+}
+
+/// Walk a `Rewrite` RHS template, replacing every `?x` with its bound
+/// subterm from `env`. An unbound `?x` is left as a literal symbol.
+pub fn substitute(template: &Mu, env: &HashMap<String, Mu>) -> Mu {
+    match template {
+        Mu::Sym(s) if is_capture(s) => {
+            let name = &s[1..];
+            env.get(name).cloned().unwrap_or_else(|| Mu::Sym(s.clone()))
+        }
+        Mu::Sym(s) => Mu::Sym(s.clone()),
+        Mu::Node(children) => {
+            Mu::Node(children.iter().map(|c| substitute(c, env)).collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_and_substitutes_swap() {
+        let pattern = Mu::Node(vec![
+            Mu::Sym("SWAP".to_string()),
+            Mu::Sym("?x".to_string()),
+            Mu::Sym("?y".to_string()),
+        ]);
+        let value = Mu::Node(vec![
+            Mu::Sym("SWAP".to_string()),
+            Mu::Sym("a".to_string()),
+            Mu::Sym("b".to_string()),
+        ]);
+
+        let mut env = HashMap::new();
+        assert!(mu_match_bind(&pattern, &value, &mut env));
+
+        let template = Mu::Node(vec![
+            Mu::Sym("SWAP".to_string()),
+            Mu::Sym("?y".to_string()),
+            Mu::Sym("?x".to_string()),
+        ]);
+        let rewritten = substitute(&template, &env);
+        assert_eq!(
+            rewritten,
+            Mu::Node(vec![
+                Mu::Sym("SWAP".to_string()),
+                Mu::Sym("b".to_string()),
+                Mu::Sym("a".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn non_linear_pattern_requires_equal_repeats() {
+        let pattern = Mu::Node(vec![Mu::Sym("?x".to_string()), Mu::Sym("?x".to_string())]);
+
+        let mut env = HashMap::new();
+        let same = Mu::Node(vec![Mu::Sym("a".to_string()), Mu::Sym("a".to_string())]);
+        assert!(mu_match_bind(&pattern, &same, &mut env));
+
+        let mut env2 = HashMap::new();
+        let different = Mu::Node(vec![Mu::Sym("a".to_string()), Mu::Sym("b".to_string())]);
+        assert!(!mu_match_bind(&pattern, &different, &mut env2));
+    }
+}
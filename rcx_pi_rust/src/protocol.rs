@@ -0,0 +1,283 @@
+//! Line-oriented wire protocol for driving a shared `Engine`/`RCXState` from
+//! another process, plus the two client shapes built on top of it.
+//!
+//! Each request is a single line of text, exactly what `Session::dispatch`
+//! or `Session::evaluate` already accepts: a `:`-prefixed command, or a bare
+//! Mu expression. Each reply is a single line of JSON (via `json_value`,
+//! this repo's hand-rolled JSON type rather than a parsing crate), so a
+//! client only has to read one line per request to know it's done.
+//!
+//! `SyncClient` mirrors a blocking "send-and-confirm" request: submit a Mu,
+//! wait for the reply, get back the route it took and the bucket it landed
+//! in. `AsyncClient` mirrors a fire-and-forget "send": enqueue a Mu without
+//! waiting for a reply at all. `Session` itself implements `SyncClient`
+//! in-process (no socket involved), and `TcpSyncClient`/`TcpAsyncClient`
+//! implement the same traits over a `TcpStream` - so `examples/repl_session`
+//! can drive a local or a remote engine through the same interface.
+
+use crate::formatter::mu_to_string;
+use crate::json_value::JsonValue;
+use crate::parser::parse_mu;
+use crate::repl::{CommandOutcome, Session};
+use crate::trace::{route_from_str, route_to_string, RouteKind};
+use crate::types::Mu;
+
+fn json_escape(s: &str) -> String {
+    let mut out = String::new();
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// The result of submitting a Mu through `SyncClient::process_and_wait`:
+/// which bucket it was routed into, and that bucket's contents afterward.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+    pub kind: RouteKind,
+    pub bucket: Vec<Mu>,
+}
+
+/// One decoded reply line, as read back by a `Tcp*Client`.
+enum Reply {
+    Lines(Vec<String>),
+    Routed(Route),
+    Err(String),
+}
+
+fn encode_lines(lines: &[String]) -> String {
+    let mut out = String::from(r#"{"ok":true,"kind":"lines","lines":["#);
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&json_escape(line));
+    }
+    out.push_str("]}");
+    out
+}
+
+fn encode_route(route: &Route) -> String {
+    let mut out = format!(
+        r#"{{"ok":true,"kind":"route","route":{},"bucket":["#,
+        json_escape(route_to_string(route.kind))
+    );
+    for (i, m) in route.bucket.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&json_escape(&mu_to_string(m)));
+    }
+    out.push_str("]}");
+    out
+}
+
+fn encode_err(message: &str) -> String {
+    format!(r#"{{"ok":false,"error":{}}}"#, json_escape(message))
+}
+
+fn bucket_for(route: RouteKind, session: &Session) -> Vec<Mu> {
+    match route {
+        RouteKind::Ra => session.state.ra.clone(),
+        RouteKind::Lobe => session.state.lobes.clone(),
+        RouteKind::Sink | RouteKind::Rewrite | RouteKind::Structural => session.state.sink.clone(),
+    }
+}
+
+/// Run one request line against `session`, returning the reply line to send
+/// back. Shared by `examples/serve`'s connection handler so the daemon's
+/// behavior is exactly `Session::dispatch`/`evaluate`, nothing more.
+pub fn handle_request(session: &mut Session, line: &str) -> String {
+    match session.dispatch(line) {
+        CommandOutcome::Handled(lines) => encode_lines(&lines),
+        CommandOutcome::NotACommand => match session.evaluate(line) {
+            Ok(route) => encode_route(&Route {
+                kind: route,
+                bucket: bucket_for(route, session),
+            }),
+            Err(e) => encode_err(&e),
+        },
+    }
+}
+
+fn decode_reply(line: &str) -> Result<Reply, String> {
+    let root = JsonValue::parse(line).map_err(|e| format!("parse reply: {e}"))?;
+    let obj = match &root {
+        JsonValue::Object(map) => map,
+        other => return Err(format!("expected a JSON object reply, got {other:?}")),
+    };
+
+    let ok = match obj.get("ok") {
+        Some(JsonValue::Bool(b)) => *b,
+        _ => return Err("reply missing boolean `ok`".to_string()),
+    };
+
+    if !ok {
+        return match obj.get("error") {
+            Some(JsonValue::String(s)) => Ok(Reply::Err(s.clone())),
+            _ => Ok(Reply::Err("server returned an error with no message".to_string())),
+        };
+    }
+
+    match obj.get("kind") {
+        Some(JsonValue::String(k)) if k == "lines" => {
+            let lines = match obj.get("lines") {
+                Some(JsonValue::Array(items)) => items
+                    .iter()
+                    .map(|v| match v {
+                        JsonValue::String(s) => Ok(s.clone()),
+                        other => Err(format!("expected string line, got {other:?}")),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+                _ => return Err("`lines` reply missing array `lines`".to_string()),
+            };
+            Ok(Reply::Lines(lines))
+        }
+        Some(JsonValue::String(k)) if k == "route" => {
+            let kind = match obj.get("route") {
+                Some(JsonValue::String(s)) => route_from_str(s)?,
+                _ => return Err("`route` reply missing string `route`".to_string()),
+            };
+            let bucket = match obj.get("bucket") {
+                Some(JsonValue::Array(items)) => items
+                    .iter()
+                    .map(|v| match v {
+                        JsonValue::String(s) => parse_mu(s).map_err(String::from),
+                        other => Err(format!("expected string mu, got {other:?}")),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+                _ => return Err("`route` reply missing array `bucket`".to_string()),
+            };
+            Ok(Reply::Routed(Route { kind, bucket }))
+        }
+        other => Err(format!("unrecognized reply kind {other:?}")),
+    }
+}
+
+/// A blocking "send-and-confirm" path: submit a Mu, wait for the engine to
+/// route it, and get back where it landed plus that bucket's new contents.
+pub trait SyncClient {
+    fn process_and_wait(&mut self, mu: Mu) -> Result<Route, String>;
+
+    /// Drive a `:`-prefixed command the same way `process_and_wait` drives
+    /// a bare Mu, returning the lines the server printed.
+    fn command(&mut self, line: &str) -> Result<Vec<String>, String>;
+}
+
+/// A fire-and-forget "send" path: enqueue a Mu without waiting for the
+/// engine to process it or for any reply at all.
+pub trait AsyncClient {
+    fn submit(&self, mu: Mu) -> Result<(), String>;
+}
+
+impl SyncClient for Session {
+    fn process_and_wait(&mut self, mu: Mu) -> Result<Route, String> {
+        let route = self
+            .engine
+            .process_input(&mut self.state, mu)
+            .ok_or_else(|| "no route produced".to_string())?;
+        Ok(Route {
+            kind: route,
+            bucket: bucket_for(route, self),
+        })
+    }
+
+    fn command(&mut self, line: &str) -> Result<Vec<String>, String> {
+        match self.dispatch(line) {
+            CommandOutcome::Handled(lines) => Ok(lines),
+            CommandOutcome::NotACommand => Err(format!("not a command: {line}")),
+        }
+    }
+}
+
+/// `SyncClient` over a `TcpStream`: one line out, one line back, per call.
+pub struct TcpSyncClient {
+    reader: std::io::BufReader<std::net::TcpStream>,
+    writer: std::net::TcpStream,
+}
+
+impl TcpSyncClient {
+    pub fn connect<A: std::net::ToSocketAddrs>(addr: A) -> Result<Self, String> {
+        let stream = std::net::TcpStream::connect(addr).map_err(|e| format!("connect: {e}"))?;
+        let writer = stream.try_clone().map_err(|e| format!("clone stream: {e}"))?;
+        Ok(Self {
+            reader: std::io::BufReader::new(stream),
+            writer,
+        })
+    }
+
+    fn round_trip(&mut self, request_line: &str) -> Result<Reply, String> {
+        use std::io::{BufRead, Write};
+
+        writeln!(self.writer, "{request_line}").map_err(|e| format!("write request: {e}"))?;
+
+        let mut reply_line = String::new();
+        let n = self
+            .reader
+            .read_line(&mut reply_line)
+            .map_err(|e| format!("read reply: {e}"))?;
+        if n == 0 {
+            return Err("server closed the connection".to_string());
+        }
+
+        decode_reply(reply_line.trim_end_matches('\n'))
+    }
+}
+
+impl SyncClient for TcpSyncClient {
+    fn process_and_wait(&mut self, mu: Mu) -> Result<Route, String> {
+        match self.round_trip(&mu_to_string(&mu))? {
+            Reply::Routed(route) => Ok(route),
+            Reply::Lines(_) => Err("expected a routed reply, got a command reply".to_string()),
+            Reply::Err(e) => Err(e),
+        }
+    }
+
+    fn command(&mut self, line: &str) -> Result<Vec<String>, String> {
+        match self.round_trip(line)? {
+            Reply::Lines(lines) => Ok(lines),
+            Reply::Routed(_) => Err("expected a command reply, got a routed reply".to_string()),
+            Reply::Err(e) => Err(e),
+        }
+    }
+}
+
+/// `AsyncClient` over a `TcpStream`: writes the request line and returns
+/// immediately, never reading a reply. The server still writes one (the
+/// protocol has no notion of a reply-less request), so a long-lived
+/// `AsyncClient` that never drains its socket will eventually back up; that
+/// trade-off is the point of "fire-and-forget" over "blocking" here.
+pub struct TcpAsyncClient {
+    writer: std::sync::Mutex<std::net::TcpStream>,
+}
+
+impl TcpAsyncClient {
+    pub fn connect<A: std::net::ToSocketAddrs>(addr: A) -> Result<Self, String> {
+        let stream = std::net::TcpStream::connect(addr).map_err(|e| format!("connect: {e}"))?;
+        Ok(Self {
+            writer: std::sync::Mutex::new(stream),
+        })
+    }
+}
+
+impl AsyncClient for TcpAsyncClient {
+    fn submit(&self, mu: Mu) -> Result<(), String> {
+        use std::io::Write;
+
+        let mut writer = self
+            .writer
+            .lock()
+            .map_err(|_| "async client writer lock poisoned".to_string())?;
+        writeln!(writer, "{}", mu_to_string(&mu)).map_err(|e| format!("write request: {e}"))
+    }
+}
@@ -0,0 +1,332 @@
+//! A small jq-like filter language over `JsonValue`, inspired by the jaq
+//! interpreter's pipeline-of-streams model: every filter maps one
+//! `JsonValue` to a *stream* (`Vec<JsonValue>`) of results, and filters
+//! compose by piping one stream into the next.
+//!
+//! Supported syntax:
+//!   .            identity
+//!   .foo         field access on an `Object`
+//!   .[n]         index into an `Array`
+//!   .[]          iterate an `Array`'s elements or an `Object`'s values
+//!   ..           recurse: the input and every value reachable from it
+//!   a | b        pipe: every output of `a` fed into `b`, streams concatenated
+//!   [ f ]        collect every output of `f` into one `Array`
+
+use crate::json_value::JsonValue;
+
+/// A parsed filter, ready to `eval` against a `JsonValue`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    Identity,
+    Field(String),
+    Index(i64),
+    Iterate,
+    Recurse,
+    Pipe(Box<Filter>, Box<Filter>),
+    Collect(Box<Filter>),
+}
+
+/// Parse `filter` and evaluate it against `input`, returning the resulting
+/// stream of `JsonValue`s in order.
+pub fn run(filter: &str, input: &JsonValue) -> Result<Vec<JsonValue>, String> {
+    let ast = parse(filter)?;
+    eval(&ast, input)
+}
+
+/// Parse a filter expression into its `Filter` AST.
+pub fn parse(src: &str) -> Result<Filter, String> {
+    let (filter, rest) = parse_pipe(src.trim())?;
+    let rest = rest.trim_start();
+    if !rest.is_empty() {
+        return Err(format!("unexpected trailing input in filter: `{rest}`"));
+    }
+    Ok(filter)
+}
+
+fn parse_pipe(s: &str) -> Result<(Filter, &str), String> {
+    let (mut lhs, mut rest) = parse_term(s)?;
+    loop {
+        let trimmed = rest.trim_start();
+        match trimmed.strip_prefix('|') {
+            Some(after_pipe) => {
+                let (rhs, tail) = parse_term(after_pipe.trim_start())?;
+                lhs = Filter::Pipe(Box::new(lhs), Box::new(rhs));
+                rest = tail;
+            }
+            None => return Ok((lhs, trimmed)),
+        }
+    }
+}
+
+/// Parse one `|`-free term: either `[ f ]`, `..`, or a `.`-rooted chain of
+/// field/index/iterate suffixes.
+fn parse_term(s: &str) -> Result<(Filter, &str), String> {
+    let s = s.trim_start();
+
+    if let Some(after_bracket) = s.strip_prefix('[') {
+        let (inner, rest) = parse_pipe(after_bracket.trim_start())?;
+        let rest = rest.trim_start();
+        let rest = rest
+            .strip_prefix(']')
+            .ok_or_else(|| "expected `]` to close array construction".to_string())?;
+        return Ok((Filter::Collect(Box::new(inner)), rest));
+    }
+
+    if let Some(rest) = s.strip_prefix("..") {
+        return Ok((Filter::Recurse, rest));
+    }
+
+    let rest = s
+        .strip_prefix('.')
+        .ok_or_else(|| format!("expected a filter starting with `.`, `..`, or `[`, got `{s}`"))?;
+
+    let mut filter = Filter::Identity;
+    let mut rest = rest;
+    loop {
+        if let Some(after) = rest.strip_prefix('[') {
+            let (index_src, after_index) = take_until(after, ']')
+                .ok_or_else(|| "expected `]` to close an index".to_string())?;
+            filter = if index_src.trim().is_empty() {
+                Filter::Pipe(Box::new(filter), Box::new(Filter::Iterate))
+            } else {
+                let n: i64 = index_src
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("invalid array index `{index_src}`"))?;
+                Filter::Pipe(Box::new(filter), Box::new(Filter::Index(n)))
+            };
+            rest = after_index;
+            continue;
+        }
+
+        if let Some(after) = rest.strip_prefix('.') {
+            let (name, after_name) = take_ident(after)?;
+            filter = Filter::Pipe(Box::new(filter), Box::new(Filter::Field(name)));
+            rest = after_name;
+            continue;
+        }
+
+        let (name, after_name) = take_ident_opt(rest);
+        if let Some(name) = name {
+            filter = Filter::Pipe(Box::new(filter), Box::new(Filter::Field(name)));
+            rest = after_name;
+            continue;
+        }
+
+        break;
+    }
+
+    Ok((filter, rest))
+}
+
+fn take_until(s: &str, close: char) -> Option<(&str, &str)> {
+    let idx = s.find(close)?;
+    Some((&s[..idx], &s[idx + close.len_utf8()..]))
+}
+
+fn take_ident(s: &str) -> Result<(String, &str), String> {
+    match take_ident_opt(s) {
+        (Some(name), rest) => Ok((name, rest)),
+        (None, _) => Err("expected a field name after `.`".to_string()),
+    }
+}
+
+fn take_ident_opt(s: &str) -> (Option<String>, &str) {
+    let end = s
+        .char_indices()
+        .find(|(_, c)| !(c.is_alphanumeric() || *c == '_'))
+        .map(|(i, _)| i)
+        .unwrap_or(s.len());
+    if end == 0 {
+        (None, s)
+    } else {
+        (Some(s[..end].to_string()), &s[end..])
+    }
+}
+
+/// A short, human-readable name for a `JsonValue`'s shape, for error messages.
+fn type_name(v: &JsonValue) -> &'static str {
+    match v {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Number(_) | JsonValue::Integer(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+    }
+}
+
+/// Evaluate `filter` against `input`, producing its output stream.
+pub fn eval(filter: &Filter, input: &JsonValue) -> Result<Vec<JsonValue>, String> {
+    match filter {
+        Filter::Identity => Ok(vec![input.clone()]),
+
+        Filter::Field(name) => match input {
+            JsonValue::Object(map) => Ok(map.get(name).cloned().into_iter().collect()),
+            other => Err(format!(
+                "cannot index {} with \"{name}\"",
+                type_name(other)
+            )),
+        },
+
+        Filter::Index(n) => match input {
+            JsonValue::Array(items) => Ok(resolve_index(items, *n).into_iter().collect()),
+            other => Err(format!("cannot index {} with number", type_name(other))),
+        },
+
+        Filter::Iterate => match input {
+            JsonValue::Array(items) => Ok(items.clone()),
+            JsonValue::Object(map) => Ok(map.values().cloned().collect()),
+            other => Err(format!("cannot iterate over {}", type_name(other))),
+        },
+
+        Filter::Recurse => {
+            let mut out = Vec::new();
+            recurse_collect(input, &mut out);
+            Ok(out)
+        }
+
+        Filter::Pipe(lhs, rhs) => {
+            let mut out = Vec::new();
+            for v in eval(lhs, input)? {
+                out.extend(eval(rhs, &v)?);
+            }
+            Ok(out)
+        }
+
+        Filter::Collect(inner) => Ok(vec![JsonValue::Array(eval(inner, input)?)]),
+    }
+}
+
+fn resolve_index(items: &[JsonValue], n: i64) -> Option<JsonValue> {
+    let len = items.len() as i64;
+    let i = if n < 0 { n + len } else { n };
+    if i < 0 || i >= len {
+        None
+    } else {
+        Some(items[i as usize].clone())
+    }
+}
+
+fn recurse_collect(v: &JsonValue, out: &mut Vec<JsonValue>) {
+    out.push(v.clone());
+    match v {
+        JsonValue::Array(items) => {
+            for item in items {
+                recurse_collect(item, out);
+            }
+        }
+        JsonValue::Object(map) => {
+            for value in map.values() {
+                recurse_collect(value, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn obj(pairs: &[(&str, JsonValue)]) -> JsonValue {
+        let mut map = BTreeMap::new();
+        for (k, v) in pairs {
+            map.insert(k.to_string(), v.clone());
+        }
+        JsonValue::Object(map)
+    }
+
+    #[test]
+    fn identity_returns_input_unchanged() {
+        let input = JsonValue::Integer(7);
+        assert_eq!(run(".", &input).unwrap(), vec![input]);
+    }
+
+    #[test]
+    fn field_access_returns_bound_value() {
+        let input = obj(&[("foo", JsonValue::Integer(1))]);
+        assert_eq!(run(".foo", &input).unwrap(), vec![JsonValue::Integer(1)]);
+    }
+
+    #[test]
+    fn field_access_on_absent_key_yields_empty_stream() {
+        let input = obj(&[("foo", JsonValue::Integer(1))]);
+        assert_eq!(run(".bar", &input).unwrap(), Vec::<JsonValue>::new());
+    }
+
+    #[test]
+    fn field_access_on_non_object_is_a_descriptive_error() {
+        let err = run(".foo", &JsonValue::Null).unwrap_err();
+        assert!(err.contains("cannot index null"), "{err}");
+
+        let err = run(".foo", &JsonValue::Integer(3)).unwrap_err();
+        assert!(err.contains("cannot index number"), "{err}");
+    }
+
+    #[test]
+    fn index_selects_array_element_with_negative_wraparound() {
+        let input = JsonValue::Array(vec![JsonValue::Integer(1), JsonValue::Integer(2), JsonValue::Integer(3)]);
+        assert_eq!(run(".[0]", &input).unwrap(), vec![JsonValue::Integer(1)]);
+        assert_eq!(run(".[-1]", &input).unwrap(), vec![JsonValue::Integer(3)]);
+        assert_eq!(run(".[5]", &input).unwrap(), Vec::<JsonValue>::new());
+    }
+
+    #[test]
+    fn iterate_yields_array_elements_or_object_values() {
+        let arr = JsonValue::Array(vec![JsonValue::Integer(1), JsonValue::Integer(2)]);
+        assert_eq!(run(".[]", &arr).unwrap(), vec![JsonValue::Integer(1), JsonValue::Integer(2)]);
+
+        let input = obj(&[("a", JsonValue::Integer(1)), ("b", JsonValue::Integer(2))]);
+        assert_eq!(run(".[]", &input).unwrap(), vec![JsonValue::Integer(1), JsonValue::Integer(2)]);
+    }
+
+    #[test]
+    fn pipe_feeds_every_output_into_the_next_filter_and_concatenates() {
+        let input = JsonValue::Array(vec![
+            obj(&[("x", JsonValue::Integer(1))]),
+            obj(&[("x", JsonValue::Integer(2))]),
+        ]);
+        assert_eq!(
+            run(".[] | .x", &input).unwrap(),
+            vec![JsonValue::Integer(1), JsonValue::Integer(2)]
+        );
+    }
+
+    #[test]
+    fn empty_stream_propagates_through_a_pipe() {
+        let input = JsonValue::Array(vec![obj(&[("x", JsonValue::Integer(1))])]);
+        assert_eq!(run(".[] | .missing | .y", &input).unwrap(), Vec::<JsonValue>::new());
+    }
+
+    #[test]
+    fn array_construction_collects_a_stream_into_one_array() {
+        let input = JsonValue::Array(vec![JsonValue::Integer(1), JsonValue::Integer(2)]);
+        assert_eq!(
+            run("[.[]]", &input).unwrap(),
+            vec![JsonValue::Array(vec![JsonValue::Integer(1), JsonValue::Integer(2)])]
+        );
+    }
+
+    #[test]
+    fn recurse_visits_input_and_every_descendant() {
+        let input = JsonValue::Array(vec![JsonValue::Integer(1), JsonValue::Array(vec![JsonValue::Integer(2)])]);
+        let got = run("..", &input).unwrap();
+        assert_eq!(
+            got,
+            vec![
+                input.clone(),
+                JsonValue::Integer(1),
+                JsonValue::Array(vec![JsonValue::Integer(2)]),
+                JsonValue::Integer(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn chained_field_and_index_access_without_explicit_pipes() {
+        let input = obj(&[("items", JsonValue::Array(vec![JsonValue::Integer(10), JsonValue::Integer(20)]))]);
+        assert_eq!(run(".items[1]", &input).unwrap(), vec![JsonValue::Integer(20)]);
+    }
+}
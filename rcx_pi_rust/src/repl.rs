@@ -0,0 +1,528 @@
+//! Core REPL plumbing shared by the interactive front-ends: multi-line entry
+//! buffering and a small set of `:`-prefixed commands that operate on a live
+//! `Engine` + `RCXState`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::engine::Engine;
+use crate::engine_json::engine_run_from_state_to_json;
+use crate::formatter::{bucket_to_string, mu_to_string};
+use crate::lint::{apply_fixes, format_diagnostics_grouped, lint_program};
+use crate::orbit::{orbit_batch, OrbitClass};
+use crate::parser::parse_mu;
+use crate::rule_dsl::parse_rule_line;
+use crate::session_io::{load_session, save_session};
+use crate::state::RCXState;
+use crate::state_io::{load_state, save_state};
+use crate::trace::RouteKind;
+use crate::types::{Mu, RcxProgram, RcxRule, RuleAction};
+
+/// The terminal attractor a (possibly transient-prefixed) `OrbitClass`
+/// settles into, e.g. `"limit cycle (period = 2)"` for both
+/// `LimitCycle { period: 2 }` and `Transient { then: LimitCycle { period: 2 }, .. }`.
+fn attractor_label(class: &OrbitClass) -> String {
+    match class {
+        OrbitClass::Transient { then, .. } => attractor_label(then),
+        OrbitClass::FixedPoint => "fixed point".to_string(),
+        OrbitClass::LimitCycle { period } => format!("limit cycle (period = {period})"),
+        OrbitClass::Unresolved { .. } => "unresolved".to_string(),
+    }
+}
+
+/// Collect every distinct cycle period reachable from `class` (through any
+/// transient prefix) into `periods`.
+fn collect_periods(class: &OrbitClass, periods: &mut BTreeSet<usize>) {
+    match class {
+        OrbitClass::Transient { then, .. } => collect_periods(then, periods),
+        OrbitClass::LimitCycle { period } => {
+            periods.insert(*period);
+        }
+        OrbitClass::FixedPoint | OrbitClass::Unresolved { .. } => {}
+    }
+}
+
+/// Render `:batch`'s output: a histogram of how many seeds settled into
+/// each attractor, followed by the distinct cycle periods found.
+fn format_batch_summary(results: &[(Mu, OrbitClass)]) -> Vec<String> {
+    let mut histogram: BTreeMap<String, usize> = BTreeMap::new();
+    let mut periods = BTreeSet::new();
+
+    for (_, class) in results {
+        *histogram.entry(attractor_label(class)).or_insert(0) += 1;
+        collect_periods(class, &mut periods);
+    }
+
+    let mut lines = vec![format!("[batch] {} seed(s) classified:", results.len())];
+    for (label, count) in histogram {
+        lines.push(format!("  {count:>4} x {label}"));
+    }
+
+    if periods.is_empty() {
+        lines.push("[batch] distinct cycle periods found: none".to_string());
+    } else {
+        let cycles = periods
+            .iter()
+            .map(usize::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        lines.push(format!("[batch] distinct cycle periods found: {cycles}"));
+    }
+
+    lines
+}
+
+/// Does `buffer` look incomplete (unbalanced brackets, or an explicit
+/// trailing `\` continuation marker) and need another line appended before
+/// we try to `parse_mu` it?
+pub fn needs_continuation(buffer: &str) -> bool {
+    if buffer.trim_end().ends_with('\\') {
+        return true;
+    }
+
+    let mut depth: i64 = 0;
+    let mut in_quotes = false;
+    let mut chars = buffer.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '\\' if in_quotes => {
+                chars.next();
+            }
+            '[' if !in_quotes => depth += 1,
+            ']' if !in_quotes => depth -= 1,
+            _ => {}
+        }
+    }
+
+    depth > 0
+}
+
+/// Append a continuation line onto an accumulated multi-line buffer,
+/// stripping a trailing `\` marker if present.
+pub fn append_continuation(buffer: &mut String, line: &str) {
+    if let Some(trimmed) = buffer.trim_end().strip_suffix('\\') {
+        buffer.truncate(trimmed.len());
+    }
+    if !buffer.is_empty() {
+        buffer.push(' ');
+    }
+    buffer.push_str(line.trim());
+}
+
+/// Result of dispatching one REPL command.
+pub enum CommandOutcome {
+    /// The line was a recognized command; `lines` is what to print.
+    Handled(Vec<String>),
+    /// The line wasn't a command at all (caller should try `parse_mu` + step).
+    NotACommand,
+}
+
+/// A minimal multi-command REPL session: a live `Engine`, `RCXState`, and the
+/// `RcxRule`s it was built from (so `:rule` can append to them and rebuild
+/// the engine).
+pub struct Session {
+    pub rules: Vec<RcxRule>,
+    pub engine: Engine,
+    pub state: RCXState,
+}
+
+impl Session {
+    pub fn new(rules: Vec<RcxRule>) -> Self {
+        let program = crate::types::RcxProgram::new(rules.clone());
+        Self {
+            rules,
+            engine: Engine::new(program),
+            state: RCXState::new(),
+        }
+    }
+
+    fn rebuild_engine(&mut self) {
+        self.engine = Engine::new(crate::types::RcxProgram::new(self.rules.clone()));
+    }
+
+    /// Dispatch one fully-assembled (non-continuation) input line.
+    /// Recognizes `:rule`, `:step`, `:trace`, `:buckets`, `:reset`, `:rules`,
+    /// `:json`, `:save`, `:load`, `:check`, `:save-session`,
+    /// `:load-session`, `:batch`.
+    pub fn dispatch(&mut self, line: &str) -> CommandOutcome {
+        if line == ":check" || line == ":check --fix" {
+            let program = RcxProgram::new(self.rules.clone());
+            let diagnostics = lint_program(&program);
+            let fix = line.ends_with("--fix");
+
+            if fix {
+                let fixed = apply_fixes(&program, &diagnostics);
+                self.rules = fixed.rules;
+                self.rebuild_engine();
+            }
+
+            let mut lines = if diagnostics.is_empty() {
+                vec!["[check] no issues found.".to_string()]
+            } else {
+                let mut lines = vec![format!("[check] {} issue(s):", diagnostics.len())];
+                lines.extend(format_diagnostics_grouped(&diagnostics).lines().map(str::to_string));
+                lines
+            };
+            if fix {
+                lines.push("[check] fixes applied.".to_string());
+            }
+            CommandOutcome::Handled(lines)
+        } else if let Some(rest) = line.strip_prefix(":rule ") {
+            match parse_rule_line(rest) {
+                Ok(rule) => {
+                    self.rules.push(rule);
+                    self.rebuild_engine();
+                    CommandOutcome::Handled(vec!["[rule] added.".to_string()])
+                }
+                Err(e) => CommandOutcome::Handled(vec![format!("[rule] error: {e}")]),
+            }
+        } else if line == ":step" {
+            match self.state.current.clone() {
+                Some(mu) => {
+                    let route = self.engine.process_input(&mut self.state, mu);
+                    CommandOutcome::Handled(vec![format!("[step] route: {:?}", route)])
+                }
+                None => {
+                    CommandOutcome::Handled(vec!["[step] nothing staged (use a bare Mu to stage one first)".to_string()])
+                }
+            }
+        } else if line == ":trace" {
+            if self.state.trace.is_empty() {
+                CommandOutcome::Handled(vec!["[trace] (empty)".to_string()])
+            } else {
+                let mut lines = vec!["[trace]".to_string()];
+                for evt in &self.state.trace {
+                    lines.push(format!(
+                        "  step {} | phase={} | route={:?} | payload={}",
+                        evt.step_index,
+                        evt.phase,
+                        evt.route,
+                        mu_to_string(&evt.payload)
+                    ));
+                }
+                CommandOutcome::Handled(lines)
+            }
+        } else if line == ":buckets" {
+            CommandOutcome::Handled(vec![
+                format!("  ra:    {}", bucket_to_string(&self.state.ra)),
+                format!("  lobes: {}", bucket_to_string(&self.state.lobes)),
+                format!("  sink:  {}", bucket_to_string(&self.state.sink)),
+            ])
+        } else if line == ":reset" {
+            self.state = RCXState::new();
+            CommandOutcome::Handled(vec!["[reset] state cleared (rules kept).".to_string()])
+        } else if line == ":rules" {
+            if self.rules.is_empty() {
+                CommandOutcome::Handled(vec!["[rules] (none loaded)".to_string()])
+            } else {
+                let mut lines = vec![format!("[rules] {} loaded:", self.rules.len())];
+                for (idx, rule) in self.rules.iter().enumerate() {
+                    let pat = mu_to_string(&rule.pattern);
+                    let action = match &rule.action {
+                        RuleAction::ToRa => "ra".to_string(),
+                        RuleAction::ToLobe => "lobe".to_string(),
+                        RuleAction::ToSink => "sink".to_string(),
+                        RuleAction::Rewrite(mu) => format!("rewrite({})", mu_to_string(mu)),
+                        RuleAction::RewriteTemplate(mu) => format!("unify({})", mu_to_string(mu)),
+                    };
+                    lines.push(format!("  {idx}: {pat} -> {action}"));
+                }
+                CommandOutcome::Handled(lines)
+            }
+        } else if line == ":json" {
+            let program = RcxProgram::new(self.rules.clone());
+            let json = engine_run_from_state_to_json("repl", &program, &mut self.state, &[]);
+            CommandOutcome::Handled(vec![json])
+        } else if let Some(path) = line.strip_prefix(":save ") {
+            match save_state(path.trim(), &self.state) {
+                Ok(()) => CommandOutcome::Handled(vec![format!("[save] wrote {}", path.trim())]),
+                Err(e) => CommandOutcome::Handled(vec![format!("[save] error: {e}")]),
+            }
+        } else if let Some(path) = line.strip_prefix(":load ") {
+            match load_state(path.trim(), &mut self.state) {
+                Ok(()) => CommandOutcome::Handled(vec![format!("[load] restored {}", path.trim())]),
+                Err(e) => CommandOutcome::Handled(vec![format!("[load] error: {e}")]),
+            }
+        } else if let Some(rest) = line.strip_prefix(":batch ") {
+            let mut args = rest.trim().splitn(2, char::is_whitespace);
+            let path = args.next().unwrap_or("").trim();
+            let max_steps: usize = args
+                .next()
+                .and_then(|s| s.trim().parse().ok())
+                .unwrap_or(64);
+
+            if path.is_empty() {
+                return CommandOutcome::Handled(vec![
+                    "[batch] usage: :batch FILE [max_steps]".to_string(),
+                ]);
+            }
+
+            match std::fs::read_to_string(path) {
+                Err(e) => CommandOutcome::Handled(vec![format!("[batch] open {path}: {e}")]),
+                Ok(text) => {
+                    let mut seeds = Vec::new();
+                    let mut parse_err = None;
+                    for (i, raw) in text.lines().enumerate() {
+                        let trimmed = raw.trim();
+                        if trimmed.is_empty() || trimmed.starts_with('#') {
+                            continue;
+                        }
+                        match parse_mu(trimmed) {
+                            Ok(mu) => seeds.push(mu),
+                            Err(e) => {
+                                parse_err = Some(format!("line {}: {e}", i + 1));
+                                break;
+                            }
+                        }
+                    }
+
+                    match parse_err {
+                        Some(e) => CommandOutcome::Handled(vec![format!("[batch] parse error: {e}")]),
+                        None => {
+                            let program = RcxProgram::new(self.rules.clone());
+                            let results = orbit_batch(&program, &seeds, max_steps);
+                            CommandOutcome::Handled(format_batch_summary(&results))
+                        }
+                    }
+                }
+            }
+        } else if let Some(path) = line.strip_prefix(":save-session ") {
+            match save_session(path.trim(), &self.rules, &self.state) {
+                Ok(()) => CommandOutcome::Handled(vec![format!(
+                    "[save-session] wrote {}",
+                    path.trim()
+                )]),
+                Err(e) => CommandOutcome::Handled(vec![format!("[save-session] error: {e}")]),
+            }
+        } else if let Some(path) = line.strip_prefix(":load-session ") {
+            match load_session(path.trim()) {
+                Ok((rules, state)) => {
+                    self.rules = rules;
+                    self.state = state;
+                    self.rebuild_engine();
+                    CommandOutcome::Handled(vec![format!(
+                        "[load-session] restored {}",
+                        path.trim()
+                    )])
+                }
+                Err(e) => CommandOutcome::Handled(vec![format!("[load-session] error: {e}")]),
+            }
+        } else {
+            CommandOutcome::NotACommand
+        }
+    }
+
+    /// Parse and route a plain (non-command) Mu expression, returning the
+    /// resulting route for the caller to print.
+    pub fn evaluate(&mut self, src: &str) -> Result<RouteKind, String> {
+        let mu: Mu = parse_mu(src)?;
+        self.engine
+            .process_input(&mut self.state, mu)
+            .ok_or_else(|| "no route produced".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unbalanced_brackets_need_continuation() {
+        assert!(needs_continuation("[A,B"));
+        assert!(!needs_continuation("[A,B]"));
+    }
+
+    #[test]
+    fn trailing_backslash_needs_continuation() {
+        assert!(needs_continuation("[A,\\"));
+    }
+
+    #[test]
+    fn brackets_inside_quotes_are_ignored() {
+        assert!(!needs_continuation(r#"["[","]"]"#));
+    }
+
+    #[test]
+    fn continuation_joins_lines_across_buffer() {
+        let mut buf = "[A,".to_string();
+        append_continuation(&mut buf, "B]");
+        assert_eq!(buf, "[A, B]");
+    }
+
+    #[test]
+    fn rule_command_adds_rewrite_rule() {
+        let mut session = Session::new(Vec::new());
+        match session.dispatch(":rule PING -> rewrite(PONG)") {
+            CommandOutcome::Handled(lines) => assert_eq!(lines, vec!["[rule] added.".to_string()]),
+            CommandOutcome::NotACommand => panic!("expected command"),
+        }
+        assert_eq!(session.rules.len(), 1);
+    }
+
+    #[test]
+    fn rule_command_accepts_a_guard_clause_and_routes_by_unification() {
+        let mut session = Session::new(Vec::new());
+        match session.dispatch(":rule [PAIR,?x,?y] | ?x == A -> rewrite(?y)") {
+            CommandOutcome::Handled(lines) => assert_eq!(lines, vec!["[rule] added.".to_string()]),
+            CommandOutcome::NotACommand => panic!("expected command"),
+        }
+        let guard = session.rules[0].guard.as_ref().expect("guard");
+        assert_eq!(guard.var, "x");
+        assert_eq!(guard.expected, Mu::Sym("A".to_string()));
+
+        let route = session.evaluate("[PAIR,A,Z]").unwrap();
+        assert_eq!(route, RouteKind::Ra);
+        assert_eq!(session.state.ra, vec![Mu::Sym("Z".to_string())]);
+    }
+
+    #[test]
+    fn rule_command_guard_rejects_a_non_matching_binding() {
+        let mut session = Session::new(Vec::new());
+        session.dispatch(":rule [PAIR,?x,?y] | ?x == A -> rewrite(?y)");
+
+        // `?x` binds to `B`, which fails the `?x == A` guard, so the rule is
+        // skipped and the input falls through to structural classification
+        // instead of firing the rewrite - `Z` never lands in `ra`.
+        session.evaluate("[PAIR,B,Z]").unwrap();
+        assert!(session.state.ra.is_empty());
+    }
+
+    #[test]
+    fn check_command_reports_no_issues_for_a_clean_program() {
+        let mut session = Session::new(Vec::new());
+        session.dispatch(":rule PING -> rewrite(PONG)");
+        match session.dispatch(":check") {
+            CommandOutcome::Handled(lines) => assert_eq!(lines, vec!["[check] no issues found.".to_string()]),
+            CommandOutcome::NotACommand => panic!("expected command"),
+        }
+    }
+
+    #[test]
+    fn rules_command_lists_loaded_rules() {
+        let mut session = Session::new(Vec::new());
+        session.dispatch(":rule PING -> rewrite(PONG)");
+        match session.dispatch(":rules") {
+            CommandOutcome::Handled(lines) => {
+                assert_eq!(lines[0], "[rules] 1 loaded:");
+                assert_eq!(lines[1], "  0: PING -> rewrite(PONG)");
+            }
+            CommandOutcome::NotACommand => panic!("expected command"),
+        }
+    }
+
+    #[test]
+    fn reset_command_clears_state_but_keeps_rules() {
+        let mut session = Session::new(Vec::new());
+        session.dispatch(":rule PING -> rewrite(PONG)");
+        session.evaluate("PING").unwrap();
+        assert!(!session.state.sink.is_empty() || !session.state.trace.is_empty());
+
+        match session.dispatch(":reset") {
+            CommandOutcome::Handled(lines) => {
+                assert_eq!(lines, vec!["[reset] state cleared (rules kept).".to_string()])
+            }
+            CommandOutcome::NotACommand => panic!("expected command"),
+        }
+        assert!(session.state.trace.is_empty());
+        assert_eq!(session.rules.len(), 1);
+    }
+
+    #[test]
+    fn json_command_emits_the_current_run() {
+        let mut session = Session::new(Vec::new());
+        session.dispatch(":rule PING -> rewrite(PONG)");
+        session.evaluate("PING").unwrap();
+
+        match session.dispatch(":json") {
+            CommandOutcome::Handled(lines) => {
+                assert_eq!(lines.len(), 1);
+                assert!(lines[0].contains(r#""world":"repl""#));
+                assert!(lines[0].contains("PONG"));
+            }
+            CommandOutcome::NotACommand => panic!("expected command"),
+        }
+    }
+
+    #[test]
+    fn check_fix_reorders_an_unreachable_rule_and_rebuilds_the_engine() {
+        let mut session = Session::new(Vec::new());
+        session.dispatch(":rule _ -> sink");
+        session.dispatch(":rule A -> ra");
+
+        match session.dispatch(":check --fix") {
+            CommandOutcome::Handled(lines) => {
+                assert!(lines.iter().any(|l| l.contains("unreachable")));
+                assert!(lines.last().unwrap().contains("fixes applied"));
+            }
+            CommandOutcome::NotACommand => panic!("expected command"),
+        }
+
+        // `A` now comes before the catch-all `_`, so it's reachable again.
+        let route = session.evaluate("A").unwrap();
+        assert_eq!(route, RouteKind::Ra);
+    }
+
+    #[test]
+    fn save_session_then_load_session_restores_rules_and_trace() {
+        let dir = std::env::temp_dir().join(format!(
+            "rcx_repl_session_test_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session.json");
+
+        let mut session = Session::new(Vec::new());
+        session.dispatch(":rule PING -> rewrite(PONG)");
+        session.evaluate("PING").unwrap();
+
+        match session.dispatch(&format!(":save-session {}", path.display())) {
+            CommandOutcome::Handled(lines) => assert!(lines[0].starts_with("[save-session] wrote")),
+            CommandOutcome::NotACommand => panic!("expected command"),
+        }
+
+        let mut fresh = Session::new(Vec::new());
+        match fresh.dispatch(&format!(":load-session {}", path.display())) {
+            CommandOutcome::Handled(lines) => assert!(lines[0].starts_with("[load-session] restored")),
+            CommandOutcome::NotACommand => panic!("expected command"),
+        }
+
+        assert_eq!(fresh.rules, session.rules);
+        assert_eq!(fresh.state.trace.len(), session.state.trace.len());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn batch_command_classifies_seeds_from_a_file() {
+        let dir = std::env::temp_dir().join(format!(
+            "rcx_repl_batch_test_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("seeds.mu");
+        std::fs::write(&path, "PING\nQQQ\n").unwrap();
+
+        let mut session = Session::new(Vec::new());
+        session.dispatch(":rule PING -> rewrite(PING)");
+
+        match session.dispatch(&format!(":batch {}", path.display())) {
+            CommandOutcome::Handled(lines) => {
+                assert_eq!(lines[0], "[batch] 2 seed(s) classified:");
+                assert!(lines.iter().any(|l| l.contains("fixed point")));
+                assert!(lines.iter().any(|l| l.contains("unresolved")));
+            }
+            CommandOutcome::NotACommand => panic!("expected command"),
+        }
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn buckets_command_reports_empty_state() {
+        let mut session = Session::new(Vec::new());
+        match session.dispatch(":buckets") {
+            CommandOutcome::Handled(lines) => assert_eq!(lines.len(), 3),
+            CommandOutcome::NotACommand => panic!("expected command"),
+        }
+    }
+}
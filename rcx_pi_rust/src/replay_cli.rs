@@ -16,6 +16,7 @@ pub struct ReplayArgs {
     pub out: Option<String>,
     pub expect: Option<String>,
     pub check_canon: bool,
+    pub diff: Option<String>,
 }
 
 /// Parse CLI arguments.
@@ -24,6 +25,7 @@ pub fn parse_args(args: &[String]) -> Result<ReplayArgs, String> {
     let mut out: Option<String> = None;
     let mut expect: Option<String> = None;
     let mut check_canon = false;
+    let mut diff: Option<String> = None;
 
     let mut i = 0;
     while i < args.len() {
@@ -52,6 +54,13 @@ pub fn parse_args(args: &[String]) -> Result<ReplayArgs, String> {
             "--check-canon" => {
                 check_canon = true;
             }
+            "--diff" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--diff requires a path".to_string());
+                }
+                diff = Some(args[i].clone());
+            }
             "--help" | "-h" => {
                 print_help();
                 return Err("".to_string()); // Signal help was shown
@@ -70,20 +79,180 @@ pub fn parse_args(args: &[String]) -> Result<ReplayArgs, String> {
         out,
         expect,
         check_canon,
+        diff,
     })
 }
 
 fn print_help() {
-    eprintln!("Usage: replay --trace <path> [--out <path>] [--expect <path>] [--check-canon]");
+    eprintln!(
+        "Usage: replay --trace <path> [--out <path>] [--expect <path>] [--check-canon] [--diff <path>]"
+    );
     eprintln!();
     eprintln!("Options:");
     eprintln!("  --trace <path>     Input trace JSONL path (required)");
     eprintln!("  --out <path>       Output path for canonicalized JSONL");
     eprintln!("  --expect <path>    Expected canonical JSONL path for comparison");
     eprintln!("  --check-canon      Fail if input is not already canonical");
+    eprintln!("  --diff <path>      On mismatch, write a structured JSON diff report here");
     eprintln!("  --help, -h         Show this help");
 }
 
+/// One line's classification in a line-level LCS diff between an `expected`
+/// and an `actual` JSONL text.
+enum LineDiffStatus {
+    Equal,
+    Added,
+    Removed,
+}
+
+impl LineDiffStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            LineDiffStatus::Equal => "equal",
+            LineDiffStatus::Added => "added",
+            LineDiffStatus::Removed => "removed",
+        }
+    }
+}
+
+struct LineDiffEntry {
+    status: LineDiffStatus,
+    line: String,
+}
+
+/// Line-level LCS diff between `expected` and `actual`: build a
+/// longest-common-subsequence DP table over line suffixes, then walk it
+/// forward from the top-left corner to classify every line as equal (in the LCS),
+/// removed (only in `expected`), or added (only in `actual`).
+fn diff_lines(expected: &[&str], actual: &[&str]) -> Vec<LineDiffEntry> {
+    let n = expected.len();
+    let m = actual.len();
+
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut entries = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if expected[i] == actual[j] {
+            entries.push(LineDiffEntry {
+                status: LineDiffStatus::Equal,
+                line: expected[i].to_string(),
+            });
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            entries.push(LineDiffEntry {
+                status: LineDiffStatus::Removed,
+                line: expected[i].to_string(),
+            });
+            i += 1;
+        } else {
+            entries.push(LineDiffEntry {
+                status: LineDiffStatus::Added,
+                line: actual[j].to_string(),
+            });
+            j += 1;
+        }
+    }
+    while i < n {
+        entries.push(LineDiffEntry {
+            status: LineDiffStatus::Removed,
+            line: expected[i].to_string(),
+        });
+        i += 1;
+    }
+    while j < m {
+        entries.push(LineDiffEntry {
+            status: LineDiffStatus::Added,
+            line: actual[j].to_string(),
+        });
+        j += 1;
+    }
+
+    entries
+}
+
+fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn opt_json(s: Option<&str>) -> String {
+    match s {
+        Some(s) => json_escape(s),
+        None => "null".to_string(),
+    }
+}
+
+/// Build a structured JSON mismatch report: the first differing line index,
+/// the expected vs actual line there, and the full per-line LCS status list.
+fn build_diff_report(kind: &str, expected: &str, actual: &str) -> String {
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let actual_lines: Vec<&str> = actual.lines().collect();
+
+    let first_diff = expected_lines
+        .iter()
+        .zip(actual_lines.iter())
+        .position(|(e, a)| e != a)
+        .unwrap_or_else(|| expected_lines.len().min(actual_lines.len()));
+    let first_diff = if first_diff < expected_lines.len() || first_diff < actual_lines.len() {
+        Some(first_diff)
+    } else {
+        None
+    };
+
+    let entries = diff_lines(&expected_lines, &actual_lines);
+
+    let mut out = String::from("{");
+    out.push_str(&format!(r#""kind":{},"#, json_escape(kind)));
+    match first_diff {
+        Some(idx) => out.push_str(&format!(r#""first_diff_line":{idx},"#)),
+        None => out.push_str(r#""first_diff_line":null,"#),
+    }
+    out.push_str(&format!(
+        r#""expected_line":{},"#,
+        opt_json(first_diff.and_then(|idx| expected_lines.get(idx).copied()))
+    ));
+    out.push_str(&format!(
+        r#""actual_line":{},"#,
+        opt_json(first_diff.and_then(|idx| actual_lines.get(idx).copied()))
+    ));
+    out.push_str(r#""lines":["#);
+    for (i, entry) in entries.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&format!(
+            r#"{{"status":{},"line":{}}}"#,
+            json_escape(entry.status.as_str()),
+            json_escape(&entry.line)
+        ));
+    }
+    out.push_str("]}");
+    out
+}
+
 /// Main replay entry point. Returns exit code.
 pub fn replay_main(args: &[String]) -> i32 {
     match replay_main_inner(args) {
@@ -120,11 +289,14 @@ fn replay_main_inner(args: &[String]) -> Result<i32, String> {
     let canon_text = canon_jsonl(&raw_events)?;
 
     // --check-canon: fail if input != canonical
-    if args.check_canon {
-        if original != canon_text {
-            eprintln!("REPLAY_MISMATCH: input trace is not canonical (diff vs canonicalized form).");
-            return Ok(EXIT_MISMATCH);
+    if args.check_canon && original != canon_text {
+        eprintln!("REPLAY_MISMATCH: input trace is not canonical (diff vs canonicalized form).");
+        if let Some(ref diff_path) = args.diff {
+            let report = build_diff_report("check_canon", &canon_text, &original);
+            fs::write(diff_path, report)
+                .map_err(|e| format!("failed to write {}: {}", diff_path, e))?;
         }
+        return Ok(EXIT_MISMATCH);
     }
 
     // --out: write canonical artifact
@@ -143,6 +315,11 @@ fn replay_main_inner(args: &[String]) -> Result<i32, String> {
             .map_err(|e| format!("failed to read {}: {}", expect_path, e))?;
         if expected != canon_text {
             eprintln!("REPLAY_MISMATCH: canonical replay output differs from --expect.");
+            if let Some(ref diff_path) = args.diff {
+                let report = build_diff_report("expect", &expected, &canon_text);
+                fs::write(diff_path, report)
+                    .map_err(|e| format!("failed to write {}: {}", diff_path, e))?;
+            }
             return Ok(EXIT_MISMATCH);
         }
     }
@@ -162,6 +339,7 @@ mod tests {
         assert!(parsed.out.is_none());
         assert!(parsed.expect.is_none());
         assert!(!parsed.check_canon);
+        assert!(parsed.diff.is_none());
     }
 
     #[test]
@@ -174,11 +352,84 @@ mod tests {
             "--expect".to_string(),
             "exp.jsonl".to_string(),
             "--check-canon".to_string(),
+            "--diff".to_string(),
+            "diff.json".to_string(),
         ];
         let parsed = parse_args(&args).unwrap();
         assert_eq!(parsed.trace, "in.jsonl");
         assert_eq!(parsed.out, Some("out.jsonl".to_string()));
         assert_eq!(parsed.expect, Some("exp.jsonl".to_string()));
         assert!(parsed.check_canon);
+        assert_eq!(parsed.diff, Some("diff.json".to_string()));
+    }
+
+    #[test]
+    fn test_parse_args_diff_requires_path() {
+        let args = vec![
+            "--trace".to_string(),
+            "test.jsonl".to_string(),
+            "--diff".to_string(),
+        ];
+        assert!(parse_args(&args).is_err());
+    }
+
+    #[test]
+    fn diff_lines_classifies_equal_added_removed() {
+        let expected = vec!["a", "b", "c"];
+        let actual = vec!["a", "x", "c"];
+        let entries = diff_lines(&expected, &actual);
+        let statuses: Vec<&str> = entries.iter().map(|e| e.status.as_str()).collect();
+        assert_eq!(statuses, vec!["equal", "removed", "added", "equal"]);
+    }
+
+    #[test]
+    fn diff_lines_identical_inputs_are_all_equal() {
+        let lines = vec!["a", "b"];
+        let entries = diff_lines(&lines, &lines);
+        assert!(entries.iter().all(|e| matches!(e.status, LineDiffStatus::Equal)));
+    }
+
+    #[test]
+    fn build_diff_report_reports_first_diff_line() {
+        let expected = "a\nb\nc\n";
+        let actual = "a\nx\nc\n";
+        let report = build_diff_report("expect", expected, actual);
+        assert!(report.contains(r#""first_diff_line":1"#));
+        assert!(report.contains(r#""expected_line":"b""#));
+        assert!(report.contains(r#""actual_line":"x""#));
+        assert!(report.contains(r#""status":"removed""#));
+        assert!(report.contains(r#""status":"added""#));
+    }
+
+    #[test]
+    fn replay_main_writes_diff_report_on_expect_mismatch() {
+        let dir = std::env::temp_dir().join(format!(
+            "rcx_replay_diff_test_{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let trace_path = dir.join("trace.jsonl");
+        let expect_path = dir.join("expect.jsonl");
+        let diff_path = dir.join("diff.json");
+
+        fs::write(&trace_path, r#"{"v":1,"type":"a","i":0}"#.to_string() + "\n").unwrap();
+        fs::write(&expect_path, r#"{"v":1,"type":"b","i":0}"#.to_string() + "\n").unwrap();
+
+        let args = vec![
+            "--trace".to_string(),
+            trace_path.to_str().unwrap().to_string(),
+            "--expect".to_string(),
+            expect_path.to_str().unwrap().to_string(),
+            "--diff".to_string(),
+            diff_path.to_str().unwrap().to_string(),
+        ];
+        let rc = replay_main(&args);
+        assert_eq!(rc, EXIT_MISMATCH);
+
+        let report = fs::read_to_string(&diff_path).unwrap();
+        assert!(report.contains(r#""kind":"expect""#));
+        assert!(report.contains(r#""first_diff_line":0"#));
+
+        let _ = fs::remove_dir_all(&dir);
     }
 }
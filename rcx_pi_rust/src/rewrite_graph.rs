@@ -0,0 +1,255 @@
+//! Breadth-first exploration of the rewrite *graph* a `RcxProgram` induces
+//! over `Mu` terms, rather than the single deterministic chain `orbit::orbit`
+//! walks.
+//!
+//! `orbit::step` always takes the *first* matching `Rewrite` rule, so an
+//! orbit follows a single chain and can't see the branches a
+//! nondeterministic rule set offers. Here every reachable `Mu` is a node and every matching
+//! `Rewrite` rule is an edge: `explore` walks the whole reachable graph
+//! breadth-first from a seed, recording a BFS tree (predecessor + firing
+//! rule per node) that `RewriteGraph::shortest_path` can replay to reconstruct
+//! the shortest rewrite sequence to any discovered term.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::matching::{match_pattern, substitute_template, Env};
+use crate::types::{Mu, RcxProgram, RcxRule, RuleAction};
+
+/// One outgoing edge from a node: the rule that fired and the term it
+/// produced. `back_edge` is `true` when `to` had already been discovered by
+/// an earlier BFS step - following it closes a cycle rather than extending
+/// the frontier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewriteEdge {
+    pub rule_i: usize,
+    pub to: Mu,
+    pub back_edge: bool,
+}
+
+/// The BFS-reachable rewrite graph rooted at a seed term.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RewriteGraph {
+    /// Discovery order: `order[0]` is the seed, `order[i]` the i-th distinct
+    /// term reached after it.
+    pub order: Vec<Mu>,
+    /// Outgoing edges per node, indexed the same as `order`. A node with no
+    /// entries is a normal form - *unless* `truncated` is set, in which case
+    /// it may simply not have been expanded yet.
+    pub edges: Vec<Vec<RewriteEdge>>,
+    /// For every non-seed node: the predecessor it was first reached from
+    /// and the rule index that produced it. This is the BFS tree
+    /// `shortest_path` replays.
+    pub predecessor: HashMap<Mu, (Mu, usize)>,
+    /// `true` if `max_nodes` was exhausted before the BFS frontier emptied -
+    /// the graph may be missing nodes and edges beyond the cap.
+    pub truncated: bool,
+}
+
+impl RewriteGraph {
+    /// `true` if `term` has no outgoing edges in the explored graph, i.e. no
+    /// `Rewrite` rule matched it. Meaningless for a term past the
+    /// `max_nodes` cap when `truncated` is set - it may just be unexpanded.
+    pub fn is_normal_form(&self, term: &Mu) -> bool {
+        self.order
+            .iter()
+            .position(|m| m == term)
+            .is_some_and(|i| self.edges[i].is_empty())
+    }
+
+    /// All discovered terms with no outgoing rewrite - the normal forms this
+    /// exploration found.
+    pub fn normal_forms(&self) -> impl Iterator<Item = &Mu> {
+        self.order
+            .iter()
+            .zip(self.edges.iter())
+            .filter(|(_, out)| out.is_empty())
+            .map(|(m, _)| m)
+    }
+
+    /// Every edge discovered to already-visited term, i.e. every cycle this
+    /// exploration closed, as `(from, edge)` pairs.
+    pub fn back_edges(&self) -> impl Iterator<Item = (&Mu, &RewriteEdge)> {
+        self.order.iter().zip(self.edges.iter()).flat_map(|(from, out)| {
+            out.iter().filter(|e| e.back_edge).map(move |e| (from, e))
+        })
+    }
+
+    /// Reconstruct the shortest rewrite sequence from the seed (`order[0]`)
+    /// to `target`, as the `(rule_i, term)` pairs applied along the way, by
+    /// walking `predecessor` backward from `target`. Returns `Some(vec![])`
+    /// if `target` *is* the seed, and `None` if `target` was never
+    /// discovered by this exploration.
+    pub fn shortest_path(&self, target: &Mu) -> Option<Vec<(usize, Mu)>> {
+        let seed = self.order.first()?;
+        if target == seed {
+            return Some(Vec::new());
+        }
+
+        let mut steps = Vec::new();
+        let mut current = target.clone();
+        while let Some((pred, rule_i)) = self.predecessor.get(&current) {
+            steps.push((*rule_i, current.clone()));
+            current = pred.clone();
+        }
+
+        if &current != seed {
+            return None;
+        }
+        steps.reverse();
+        Some(steps)
+    }
+}
+
+/// Every `Rewrite` rule that matches `term`, as `(rule index, rewritten
+/// term)` pairs - unlike `orbit::step`, which stops at the first match.
+fn rewrites_from(program: &RcxProgram, term: &Mu) -> Vec<(usize, Mu)> {
+    let mut out = Vec::new();
+    for (rule_i, RcxRule { pattern, action, .. }) in program.rules.iter().enumerate() {
+        if let RuleAction::Rewrite(template) = action {
+            let mut env: Env = Env::new();
+            if match_pattern(pattern, term, &mut env) {
+                out.push((rule_i, substitute_template(template, &env)));
+            }
+        }
+    }
+    out
+}
+
+/// Breadth-first search of the rewrite graph reachable from `seed`, up to
+/// `max_nodes` distinct terms. At each dequeued term, every matching
+/// `Rewrite` rule is followed (not just the first): new terms extend the
+/// BFS frontier and are recorded in the predecessor tree, while edges back
+/// to an already-discovered term are kept as `back_edge` entries instead of
+/// being re-explored.
+pub fn explore(program: &RcxProgram, seed: Mu, max_nodes: usize) -> RewriteGraph {
+    let mut order = vec![seed.clone()];
+    let mut index_of: HashMap<Mu, usize> = HashMap::new();
+    index_of.insert(seed.clone(), 0);
+    let mut edges: Vec<Vec<RewriteEdge>> = vec![Vec::new()];
+    let mut predecessor: HashMap<Mu, (Mu, usize)> = HashMap::new();
+    let mut queue: VecDeque<Mu> = VecDeque::new();
+    queue.push_back(seed);
+    let mut truncated = false;
+
+    while let Some(term) = queue.pop_front() {
+        let term_idx = index_of[&term];
+        let mut out_edges = Vec::new();
+
+        for (rule_i, next) in rewrites_from(program, &term) {
+            let back_edge = index_of.contains_key(&next);
+            if !back_edge {
+                if order.len() >= max_nodes {
+                    truncated = true;
+                    continue;
+                }
+                index_of.insert(next.clone(), order.len());
+                predecessor.insert(next.clone(), (term.clone(), rule_i));
+                order.push(next.clone());
+                edges.push(Vec::new());
+                queue.push_back(next.clone());
+            }
+            out_edges.push(RewriteEdge { rule_i, to: next, back_edge });
+        }
+
+        edges[term_idx] = out_edges;
+    }
+
+    RewriteGraph {
+        order,
+        edges,
+        predecessor,
+        truncated,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sym(s: &str) -> Mu {
+        Mu::Sym(s.to_string())
+    }
+
+    #[test]
+    fn chain_to_normal_form() {
+        // X -> Y -> Z, Z has no rule.
+        let program = RcxProgram {
+            rules: vec![
+                RcxRule::new(sym("X"), RuleAction::Rewrite(sym("Y"))),
+                RcxRule::new(sym("Y"), RuleAction::Rewrite(sym("Z"))),
+            ],
+        };
+
+        let graph = explore(&program, sym("X"), 10);
+        assert_eq!(graph.order, vec![sym("X"), sym("Y"), sym("Z")]);
+        assert!(graph.is_normal_form(&sym("Z")));
+        assert!(!graph.truncated);
+        assert_eq!(graph.normal_forms().collect::<Vec<_>>(), vec![&sym("Z")]);
+    }
+
+    #[test]
+    fn detects_a_cycle_as_a_back_edge() {
+        // ping -> pong -> ping
+        let program = RcxProgram {
+            rules: vec![
+                RcxRule::new(sym("ping"), RuleAction::Rewrite(sym("pong"))),
+                RcxRule::new(sym("pong"), RuleAction::Rewrite(sym("ping"))),
+            ],
+        };
+
+        let graph = explore(&program, sym("ping"), 10);
+        assert_eq!(graph.order, vec![sym("ping"), sym("pong")]);
+        assert_eq!(graph.back_edges().count(), 1);
+        let (from, edge) = graph.back_edges().next().unwrap();
+        assert_eq!(from, &sym("pong"));
+        assert_eq!(edge.to, sym("ping"));
+        assert!(!graph.is_normal_form(&sym("ping")));
+        assert!(!graph.is_normal_form(&sym("pong")));
+    }
+
+    #[test]
+    fn branches_on_every_matching_rule() {
+        // X rewrites two different ways; both successors are reachable.
+        let program = RcxProgram {
+            rules: vec![
+                RcxRule::new(sym("X"), RuleAction::Rewrite(sym("A"))),
+                RcxRule::new(sym("X"), RuleAction::Rewrite(sym("B"))),
+            ],
+        };
+
+        let graph = explore(&program, sym("X"), 10);
+        assert_eq!(graph.order.len(), 3);
+        assert!(graph.order.contains(&sym("A")));
+        assert!(graph.order.contains(&sym("B")));
+        assert_eq!(graph.edges[0].len(), 2);
+    }
+
+    #[test]
+    fn shortest_path_reconstructs_rewrite_sequence() {
+        let program = RcxProgram {
+            rules: vec![
+                RcxRule::new(sym("X"), RuleAction::Rewrite(sym("Y"))),
+                RcxRule::new(sym("Y"), RuleAction::Rewrite(sym("Z"))),
+            ],
+        };
+
+        let graph = explore(&program, sym("X"), 10);
+        assert_eq!(graph.shortest_path(&sym("X")), Some(Vec::new()));
+        assert_eq!(
+            graph.shortest_path(&sym("Z")),
+            Some(vec![(0, sym("Y")), (1, sym("Z"))])
+        );
+        assert_eq!(graph.shortest_path(&sym("nope")), None);
+    }
+
+    #[test]
+    fn truncation_is_reported() {
+        let program = RcxProgram {
+            rules: vec![RcxRule::new(sym("X"), RuleAction::Rewrite(sym("Y")))],
+        };
+
+        let graph = explore(&program, sym("X"), 1);
+        assert!(graph.truncated);
+        assert_eq!(graph.order, vec![sym("X")]);
+    }
+}
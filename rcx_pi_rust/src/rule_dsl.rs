@@ -0,0 +1,323 @@
+//! Grammar for the `<pattern> -> <action>` rule DSL used by snapshot
+//! save/load (`snapshot_json`) and re-used by `lint`'s autofix.
+//!
+//! `parse_rule_line` replaces a brittle `line.split("->")`, which breaks the
+//! moment a quoted Mu symbol contains a literal `->` or a `#`. Instead this
+//! walks the line with a small set of parser-combinator primitives over a
+//! char cursor (`ws`, `lit`, `ident`, `term`), tracking bracket/quote depth
+//! so the arrow, an optional guard clause, and a trailing comment are all
+//! found at the top level rather than by naive substring search.
+//!
+//! Surface syntax:
+//!   <pattern> [ | ?var == <term> ] -> ra|lobe|sink|rewrite|unify <term> [ # comment ]
+//!
+//! `unify <term>` is `rewrite`'s sibling for `RuleAction::RewriteTemplate`:
+//! the pattern is matched (and the template instantiated) via `unify`'s
+//! single-uppercase-letter variables (e.g. `X`) instead of `rewrite`'s `?x`
+//! captures.
+
+use crate::parser::parse_mu;
+use crate::types::{Guard, RcxRule, RuleAction};
+
+/// A cursor over the remaining input, advanced one combinator at a time.
+struct Cursor<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(s: &'a str) -> Self {
+        Cursor { rest: s }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest.chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.rest = &self.rest[c.len_utf8()..];
+        Some(c)
+    }
+
+    /// Whitespace combinator: consume zero or more spaces/tabs.
+    fn ws(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+            self.bump();
+        }
+    }
+
+    /// Literal-tag combinator: consume `tag` if the input starts with it.
+    fn lit(&mut self, tag: &str) -> bool {
+        if self.rest.starts_with(tag) {
+            self.rest = &self.rest[tag.len()..];
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Identifier combinator: one or more alphanumeric/`_` characters.
+    fn ident(&mut self) -> Option<String> {
+        let end = self
+            .rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(self.rest.len());
+        if end == 0 {
+            return None;
+        }
+        let (name, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        Some(name.to_string())
+    }
+
+    /// Term combinator: scan a Mu term's source text, respecting `[`/`]`
+    /// nesting and `"..."` quoting (with `\`-escapes), stopping at the
+    /// first top-level `|`, `->`, or `#`, or end of input.
+    fn term(&mut self) -> Result<String, String> {
+        let mut depth: i32 = 0;
+        let mut end = self.rest.len();
+        let mut chars = self.rest.char_indices();
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '[' => depth += 1,
+                ']' => {
+                    depth -= 1;
+                    if depth < 0 {
+                        return Err(format!("unbalanced `]` in `{}`", self.rest));
+                    }
+                }
+                '"' => loop {
+                    match chars.next() {
+                        None => return Err(format!("unterminated quoted symbol in `{}`", self.rest)),
+                        Some((_, '\\')) => {
+                            chars.next();
+                        }
+                        Some((_, '"')) => break,
+                        Some(_) => {}
+                    }
+                },
+                '|' | '#' if depth == 0 => {
+                    end = i;
+                    break;
+                }
+                '-' if depth == 0 && self.rest[i..].starts_with("->") => {
+                    end = i;
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        if depth != 0 {
+            return Err(format!("unbalanced `[` in `{}`", self.rest));
+        }
+
+        let (text, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        let text = text.trim();
+        if text.is_empty() {
+            return Err("expected a Mu term".to_string());
+        }
+        Ok(text.to_string())
+    }
+}
+
+fn parse_guard(cursor: &mut Cursor) -> Result<Guard, String> {
+    cursor.ws();
+    if !cursor.lit("?") {
+        return Err("guard must start with `?<var>`".to_string());
+    }
+    let var = cursor
+        .ident()
+        .ok_or_else(|| "expected a variable name after `?` in guard".to_string())?;
+    cursor.ws();
+    if !cursor.lit("==") {
+        return Err(format!("expected `==` in guard for `?{var}`"));
+    }
+    cursor.ws();
+    let expected_src = cursor.term()?;
+    let expected =
+        parse_mu(&expected_src).map_err(|e| format!("parse guard value `{expected_src}`: {e}"))?;
+    Ok(Guard { var, expected })
+}
+
+fn parse_action(src: &str) -> Result<RuleAction, String> {
+    let trimmed = src.trim();
+    let lower = trimmed.to_lowercase();
+    if lower.starts_with("rewrite") {
+        let payload_src = trimmed["rewrite".len()..].trim();
+        // Accept both `rewrite <term>` (this DSL's own surface syntax) and
+        // `rewrite(<term>)` (mu_loader's world-file syntax), so callers like
+        // the REPL can use either without this grammar rejecting the other.
+        let payload_src = payload_src
+            .strip_prefix('(')
+            .and_then(|s| s.strip_suffix(')'))
+            .unwrap_or(payload_src)
+            .trim();
+        let mu = parse_mu(payload_src)
+            .map_err(|e| format!("parse rewrite payload `{payload_src}`: {e}"))?;
+        return Ok(RuleAction::Rewrite(mu));
+    }
+    if lower.starts_with("unify") {
+        let payload_src = trimmed["unify".len()..].trim();
+        let mu = parse_mu(payload_src)
+            .map_err(|e| format!("parse unify payload `{payload_src}`: {e}"))?;
+        return Ok(RuleAction::RewriteTemplate(mu));
+    }
+    match lower.as_str() {
+        "ra" => Ok(RuleAction::ToRa),
+        "lobe" | "lobes" => Ok(RuleAction::ToLobe),
+        "sink" => Ok(RuleAction::ToSink),
+        other => Err(format!("unknown rule target `{other}`")),
+    }
+}
+
+/// Parse one `.mu`-style rule line: `<pattern> [| <guard>] -> <action> [# comment]`.
+pub fn parse_rule_line(line: &str) -> Result<RcxRule, String> {
+    let mut cursor = Cursor::new(line);
+    cursor.ws();
+
+    let pattern_src = cursor.term()?;
+    let pattern =
+        parse_mu(&pattern_src).map_err(|e| format!("parse pattern `{pattern_src}`: {e}"))?;
+
+    cursor.ws();
+    let guard = if cursor.lit("|") {
+        Some(parse_guard(&mut cursor)?)
+    } else {
+        None
+    };
+
+    cursor.ws();
+    if !cursor.lit("->") {
+        return Err(format!("bad rule line: `{line}` (expected `->`)"));
+    }
+    cursor.ws();
+
+    let action_src = cursor.term()?;
+    let action = parse_action(&action_src)?;
+
+    cursor.ws();
+    let comment = if cursor.lit("#") {
+        Some(cursor.rest.trim().to_string())
+    } else {
+        None
+    };
+
+    Ok(RcxRule {
+        pattern,
+        guard,
+        action,
+        comment,
+    })
+}
+
+/// Re-emit a rule as DSL source, including its guard and trailing comment
+/// (if any) so a save/load round trip through `parse_rule_line` is stable.
+pub fn rule_to_string(rule: &RcxRule) -> String {
+    use crate::formatter::mu_to_string;
+
+    let mut out = mu_to_string(&rule.pattern);
+
+    if let Some(guard) = &rule.guard {
+        out.push_str(&format!(" | ?{} == {}", guard.var, mu_to_string(&guard.expected)));
+    }
+
+    out.push_str(" -> ");
+    out.push_str(&match &rule.action {
+        RuleAction::ToRa => "ra".to_string(),
+        RuleAction::ToLobe => "lobe".to_string(),
+        RuleAction::ToSink => "sink".to_string(),
+        RuleAction::Rewrite(mu) => format!("rewrite {}", mu_to_string(mu)),
+        RuleAction::RewriteTemplate(mu) => format!("unify {}", mu_to_string(mu)),
+    });
+
+    if let Some(comment) = &rule.comment {
+        out.push_str(" # ");
+        out.push_str(comment);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Mu;
+
+    #[test]
+    fn parses_plain_rule() {
+        let rule = parse_rule_line("A -> ra").unwrap();
+        assert_eq!(rule.pattern, Mu::Sym("A".to_string()));
+        assert!(rule.guard.is_none());
+        assert!(rule.comment.is_none());
+        assert!(matches!(rule.action, RuleAction::ToRa));
+    }
+
+    #[test]
+    fn parses_rewrite_rule() {
+        let rule = parse_rule_line("PING -> rewrite PONG").unwrap();
+        assert_eq!(
+            rule.action,
+            RuleAction::Rewrite(Mu::Sym("PONG".to_string()))
+        );
+    }
+
+    #[test]
+    fn parses_rewrite_rule_with_parens() {
+        let rule = parse_rule_line("PING -> rewrite(PONG)").unwrap();
+        assert_eq!(
+            rule.action,
+            RuleAction::Rewrite(Mu::Sym("PONG".to_string()))
+        );
+    }
+
+    #[test]
+    fn parses_unify_rule() {
+        let rule = parse_rule_line("[PAIR,X] -> unify X").unwrap();
+        assert_eq!(rule.action, RuleAction::RewriteTemplate(Mu::Sym("X".to_string())));
+    }
+
+    #[test]
+    fn round_trips_unify_rule_through_rule_to_string() {
+        let rule = parse_rule_line("[PAIR,X] -> unify X").unwrap();
+        let text = rule_to_string(&rule);
+        let reparsed = parse_rule_line(&text).unwrap();
+        assert_eq!(reparsed, rule);
+    }
+
+    #[test]
+    fn parses_guard_clause() {
+        let rule = parse_rule_line("[PAIR,?x,?y] | ?x == A -> ra").unwrap();
+        let guard = rule.guard.expect("guard");
+        assert_eq!(guard.var, "x");
+        assert_eq!(guard.expected, Mu::Sym("A".to_string()));
+    }
+
+    #[test]
+    fn parses_trailing_comment() {
+        let rule = parse_rule_line("A -> ra # only A routes to ra").unwrap();
+        assert_eq!(rule.comment.as_deref(), Some("only A routes to ra"));
+    }
+
+    #[test]
+    fn arrow_inside_quoted_symbol_is_not_mistaken_for_the_arrow() {
+        let rule = parse_rule_line(r#""a->b" -> ra"#).unwrap();
+        assert_eq!(rule.pattern, Mu::Sym("a->b".to_string()));
+    }
+
+    #[test]
+    fn round_trips_guard_and_comment_through_rule_to_string() {
+        let rule = parse_rule_line("[PAIR,?x,?y] | ?x == A -> rewrite ?y # swap guard").unwrap();
+        let text = rule_to_string(&rule);
+        let reparsed = parse_rule_line(&text).unwrap();
+        assert_eq!(reparsed, rule);
+    }
+
+    #[test]
+    fn unknown_target_still_errors() {
+        let err = parse_rule_line("A -> sinkk").unwrap_err();
+        assert!(err.contains("unknown rule target"));
+    }
+}
@@ -5,6 +5,7 @@ use crate::state::RCXState;
 use crate::trace::RouteKind;
 use crate::traits::{Classification, classify};
 use crate::types::{Mu, RcxProgram, RcxRule, RuleAction};
+use crate::unify::{apply_subst, unify};
 
 /// Pure structural step (no explicit program):
 /// - tries to reduce the current term
@@ -36,6 +37,7 @@ pub fn step(state: &mut RCXState) {
 /// Program-aware classification:
 /// 1. Try explicit RcxRule patterns
 /// 2. If no rule matches, fall back to structural classification (same as `step`)
+///
 /// Returns the route used, if any.
 pub fn classify_with_program(state: &mut RCXState, program: &RcxProgram) -> Option<RouteKind> {
     let current = match &state.current {
@@ -44,7 +46,7 @@ pub fn classify_with_program(state: &mut RCXState, program: &RcxProgram) -> Opti
     };
 
     // 1) Try explicit rules first
-    for RcxRule { pattern, action } in &program.rules {
+    for RcxRule { pattern, action, .. } in &program.rules {
         if &current == pattern {
             let route = match action {
                 RuleAction::ToRa => {
@@ -64,12 +66,20 @@ pub fn classify_with_program(state: &mut RCXState, program: &RcxProgram) -> Opti
                     state.current = Some(target.clone());
                     RouteKind::Rewrite
                 }
+                RuleAction::RewriteTemplate(template) => {
+                    // `pattern` already matched `current` by strict equality
+                    // above, so this just binds any pattern variables against
+                    // it before instantiating the template.
+                    let subst = unify(pattern, &current).unwrap_or_default();
+                    state.current = Some(apply_subst(template, &subst));
+                    RouteKind::Rewrite
+                }
             };
 
             state.log_event("classify_with_program(rule)", route, current);
 
             // If we projected, clear current; if we rewrote, it's already updated.
-            if !matches!(action, RuleAction::Rewrite(_)) {
+            if !matches!(action, RuleAction::Rewrite(_) | RuleAction::RewriteTemplate(_)) {
                 state.current = None;
             }
 
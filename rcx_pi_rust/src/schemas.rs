@@ -3,3 +3,5 @@
 /// Keep these as constants so external tooling can rely on them.
 pub const ORBIT_SCHEMA_V1: &str = "rcx.orbit.v1";
 pub const ENGINE_RUN_SCHEMA_V1: &str = "rcx.engine_run.v1";
+pub const SNAPSHOT_SCHEMA_V1: &str = "rcx.snapshot.v1";
+pub const SNAPSHOT_SCHEMA_V2: &str = "rcx.snapshot.v2";
@@ -38,6 +38,10 @@ pub fn save_state(path: &str, state: &RCXState, program: &RcxProgram) -> Result<
                 let rhs = mu_to_string(mu);
                 format!("RULE: {pat} -> rewrite {rhs}")
             }
+            RuleAction::RewriteTemplate(mu) => {
+                let rhs = mu_to_string(mu);
+                format!("RULE: {pat} -> unify {rhs}")
+            }
         };
         write_line(&mut file, &line)?;
     }
@@ -105,6 +109,11 @@ pub fn load_state(path: &str) -> Result<(RCXState, RcxProgram), String> {
                 let mu = parse_mu(payload_src)
                     .map_err(|e| format!("parse rewrite payload `{payload_src}` in {path}: {e}"))?;
                 RuleAction::Rewrite(mu)
+            } else if rhs_lower.starts_with("unify ") {
+                let payload_src = &rhs_src["unify".len()..].trim();
+                let mu = parse_mu(payload_src)
+                    .map_err(|e| format!("parse unify payload `{payload_src}` in {path}: {e}"))?;
+                RuleAction::RewriteTemplate(mu)
             } else {
                 match rhs_lower.as_str() {
                     "ra" => RuleAction::ToRa,
@@ -114,7 +123,7 @@ pub fn load_state(path: &str) -> Result<(RCXState, RcxProgram), String> {
                 }
             };
 
-            program.rules.push(RcxRule { pattern, action });
+            program.rules.push(RcxRule::new(pattern, action));
             continue;
         }
 
@@ -151,6 +160,7 @@ pub fn load_state(path: &str) -> Result<(RCXState, RcxProgram), String> {
         inf_reg: Vec::new(),
         trace: Vec::new(),
         step_counter: 0,
+        clock: None,
     };
 
     Ok((state, program))
@@ -26,7 +26,7 @@ fn escape_json_string(s: &str) -> String {
 ///   "rules": [
 ///     {
 ///       "pattern": "<mu-as-text>",
-///       "action": "ra" | "lobe" | "sink" | "rewrite",
+///       "action": "ra" | "lobe" | "sink" | "rewrite" | "unify",   // "unify" rules carry "rewrite" too
 ///       "rewrite": "<mu-as-text>"   // only for rewrite rules
 ///     },
 ///     ...
@@ -40,7 +40,7 @@ pub fn export_world_json(name: &str, program: &RcxProgram) -> Result<String, Str
     let mut out = String::new();
     out.push_str("{\n  \"rules\": [\n");
 
-    for (i, RcxRule { pattern, action }) in program.rules.iter().enumerate() {
+    for (i, RcxRule { pattern, action, .. }) in program.rules.iter().enumerate() {
         if i > 0 {
             out.push_str(",\n");
         }
@@ -55,6 +55,10 @@ pub fn export_world_json(name: &str, program: &RcxProgram) -> Result<String, Str
                 let rw = escape_json_string(&mu_to_string(mu));
                 ("rewrite", Some(rw))
             }
+            RuleAction::RewriteTemplate(mu) => {
+                let rw = escape_json_string(&mu_to_string(mu));
+                ("unify", Some(rw))
+            }
         };
 
         out.push_str("    {\n");
@@ -0,0 +1,97 @@
+//! Whole-session persistence: program rules *and* state (buckets, trace,
+//! step counter, null/inf registers) round-tripped through a single
+//! `rcx.snapshot.v2` JSON file via `snapshot_json`.
+//!
+//! Unlike `state_io::save_state`/`load_state`, which only ever kept the
+//! three buckets as lossy `ra = [...]` text lines and silently dropped the
+//! program and trace, a session file is self-describing and diffable, and
+//! `load_session` rejects a document tagged with the wrong schema instead
+//! of handing back a half-restored state.
+
+use std::path::Path;
+
+use crate::snapshot_json::{snapshot_from_json_v2, snapshot_to_json_v2};
+use crate::state::RCXState;
+use crate::types::{RcxProgram, RcxRule};
+
+/// The world name is baked into the snapshot envelope but isn't otherwise
+/// meaningful to a REPL `Session`, which has no notion of named worlds.
+const SESSION_WORLD: &str = "session";
+
+/// Save `rules` and `state` together as one `rcx.snapshot.v2` file.
+pub fn save_session<P: AsRef<Path>>(path: P, rules: &[RcxRule], state: &RCXState) -> Result<(), String> {
+    let path = path.as_ref();
+
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("create session dir {}: {e}", parent.display()))?;
+        }
+    }
+
+    let program = RcxProgram::new(rules.to_vec());
+    let json = snapshot_to_json_v2(SESSION_WORLD, &program, state);
+    std::fs::write(path, json).map_err(|e| format!("write session file {}: {e}", path.display()))
+}
+
+/// Load a session file written by `save_session`, returning the restored
+/// rules and state. Rejects a document whose `schema` isn't
+/// `rcx.snapshot.v2` rather than silently dropping whatever it can't parse.
+pub fn load_session<P: AsRef<Path>>(path: P) -> Result<(Vec<RcxRule>, RCXState), String> {
+    let path = path.as_ref();
+
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| format!("open session file {}: {e}", path.display()))?;
+    let (_, program, state) = snapshot_from_json_v2(SESSION_WORLD, &json)?;
+
+    Ok((program.rules, state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Mu, RuleAction};
+
+    #[test]
+    fn round_trips_rules_and_trace_through_a_file() {
+        let rules = vec![RcxRule::new(
+            Mu::Sym("PING".to_string()),
+            RuleAction::Rewrite(Mu::Sym("PONG".to_string())),
+        )];
+        let mut engine = crate::engine::Engine::new(RcxProgram::new(rules.clone()));
+        let mut state = RCXState::new();
+        let _ = engine.process_input(&mut state, Mu::Sym("PING".to_string()));
+
+        let dir = std::env::temp_dir().join(format!(
+            "rcx_session_io_test_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session.json");
+
+        save_session(&path, &rules, &state).unwrap();
+        let (restored_rules, restored_state) = load_session(&path).unwrap();
+
+        assert_eq!(restored_rules, rules);
+        assert_eq!(restored_state.trace.len(), state.trace.len());
+        assert_eq!(restored_state.step_counter, state.step_counter);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn rejects_a_file_with_the_wrong_schema() {
+        let dir = std::env::temp_dir().join(format!(
+            "rcx_session_io_test_bad_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session.json");
+        std::fs::write(&path, r#"{"schema":"rcx.snapshot.v1","world":"w","program":{"rules":[]},"state":{"current":null,"ra":[],"lobes":[],"sink":[],"null_reg":[],"inf_reg":[],"step_counter":0}}"#).unwrap();
+
+        let err = load_session(&path).unwrap_err();
+        assert!(err.contains("schema mismatch"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,8 +1,9 @@
 // src/state.rs
+use crate::clock::Clock;
 use crate::trace::{RouteKind, TraceEvent};
 use crate::types::Mu;
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct RCXState {
     pub current: Option<Mu>,
     pub ra: Vec<Mu>,
@@ -14,6 +15,32 @@ pub struct RCXState {
     // Trace of what the engine did over time
     pub trace: Vec<TraceEvent>,
     pub step_counter: usize,
+
+    /// Optional time source used to stamp each `TraceEvent.t`. `None` (the
+    /// default) means events are logged without a timestamp.
+    pub clock: Option<Box<dyn Clock>>,
+}
+
+impl Clone for RCXState {
+    fn clone(&self) -> Self {
+        Self {
+            current: self.current.clone(),
+            ra: self.ra.clone(),
+            lobes: self.lobes.clone(),
+            sink: self.sink.clone(),
+            null_reg: self.null_reg.clone(),
+            inf_reg: self.inf_reg.clone(),
+            trace: self.trace.clone(),
+            step_counter: self.step_counter,
+            clock: self.clock.as_ref().map(|c| c.clone_box()),
+        }
+    }
+}
+
+impl Default for RCXState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl RCXState {
@@ -27,6 +54,7 @@ impl RCXState {
             inf_reg: Vec::new(),
             trace: Vec::new(),
             step_counter: 0,
+            clock: None,
         }
     }
 
@@ -36,14 +64,22 @@ impl RCXState {
         s
     }
 
-    /// Log a trace event into this state.
+    /// Install the clock used to stamp future trace events' `t` field.
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        self.clock = Some(clock);
+    }
+
+    /// Log a trace event into this state, stamped with `t` from the
+    /// installed clock (if any).
     pub fn log_event(&mut self, phase: &str, route: RouteKind, payload: Mu) {
         self.step_counter += 1;
+        let t = self.clock.as_ref().map(|c| c.now_rfc3339());
         self.trace.push(TraceEvent {
             step_index: self.step_counter,
             phase: phase.to_string(),
             route,
             payload,
+            t,
         });
     }
 }
@@ -3,6 +3,7 @@ use std::io::{BufRead, BufReader, Write};
 use std::path::Path;
 
 use crate::formatter::bucket_to_string;
+use crate::mu_codec;
 use crate::parser::parse_mu;
 use crate::state::RCXState;
 use crate::types::Mu;
@@ -84,3 +85,63 @@ pub fn load_state<P: AsRef<Path>>(path: P, state: &mut RCXState) -> Result<(), S
 
     Ok(())
 }
+
+/// Save only the bucket state (r_a, lobes, sink) using the canonical binary
+/// `mu_codec`, for large worlds where the text format is too bulky.
+///
+/// On disk this is a single encoded `Node([Node(ra), Node(lobes), Node(sink)])`.
+pub fn save_state_binary<P: AsRef<Path>>(path: P, state: &RCXState) -> Result<(), String> {
+    let path = path.as_ref();
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("create state dir {}: {e}", parent.display()))?;
+    }
+
+    let snapshot = Mu::Node(vec![
+        Mu::Node(state.ra.clone()),
+        Mu::Node(state.lobes.clone()),
+        Mu::Node(state.sink.clone()),
+    ]);
+
+    let mut file =
+        File::create(path).map_err(|e| format!("create state file {}: {e}", path.display()))?;
+    file.write_all(&mu_codec::encode(&snapshot))
+        .map_err(|e| format!("write state file {}: {e}", path.display()))
+}
+
+/// Load bucket state (r_a, lobes, sink) from a file written by
+/// [`save_state_binary`]. Does NOT touch program rules, and clears
+/// trace/step counter so the run is fresh.
+pub fn load_state_binary<P: AsRef<Path>>(path: P, state: &mut RCXState) -> Result<(), String> {
+    let path = path.as_ref();
+
+    let bytes = std::fs::read(path).map_err(|e| format!("open state file {}: {e}", path.display()))?;
+    let snapshot = mu_codec::decode_one(&bytes)
+        .map_err(|e| format!("decode state file {}: {e}", path.display()))?;
+
+    let buckets = match snapshot {
+        Mu::Node(buckets) if buckets.len() == 3 => buckets,
+        _ => return Err(format!("malformed binary state file {}", path.display())),
+    };
+
+    let as_children = |mu: Mu| -> Result<Vec<Mu>, String> {
+        match mu {
+            Mu::Node(children) => Ok(children),
+            other => Ok(vec![other]),
+        }
+    };
+
+    let mut iter = buckets.into_iter();
+    state.ra = as_children(iter.next().unwrap())?;
+    state.lobes = as_children(iter.next().unwrap())?;
+    state.sink = as_children(iter.next().unwrap())?;
+
+    state.current = None;
+    state.trace.clear();
+    state.step_counter = 0;
+    state.null_reg.clear();
+    state.inf_reg.clear();
+
+    Ok(())
+}
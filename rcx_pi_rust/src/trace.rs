@@ -17,17 +17,50 @@ pub struct TraceEvent {
     pub phase: String,
     pub route: RouteKind,
     pub payload: Mu,
+    /// Wall-clock stamp from `RCXState`'s installed `Clock`, RFC 3339.
+    /// `None` when no clock is installed (the default).
+    pub t: Option<String>,
+}
+
+/// Stable textual encoding of a `RouteKind`, suitable for round-tripping
+/// through a snapshot's trace array.
+pub fn route_to_string(route: RouteKind) -> &'static str {
+    match route {
+        RouteKind::Ra => "ra",
+        RouteKind::Lobe => "lobe",
+        RouteKind::Sink => "sink",
+        RouteKind::Rewrite => "rewrite",
+        RouteKind::Structural => "structural",
+    }
+}
+
+/// Parse a `RouteKind` back from `route_to_string`'s output.
+pub fn route_from_str(s: &str) -> Result<RouteKind, String> {
+    match s {
+        "ra" => Ok(RouteKind::Ra),
+        "lobe" => Ok(RouteKind::Lobe),
+        "sink" => Ok(RouteKind::Sink),
+        "rewrite" => Ok(RouteKind::Rewrite),
+        "structural" => Ok(RouteKind::Structural),
+        other => Err(format!("unknown route kind `{other}`")),
+    }
 }
 
 /// Helper: append a trace event to the state’s log.
+///
+/// Superseded by `RCXState::log_event`, which also stamps `t` from the
+/// state's installed `Clock`; kept for callers that only have a state and a
+/// route/payload in hand.
 pub fn log_event(state: &mut crate::state::RCXState, phase: &str, route: RouteKind, payload: Mu) {
     state.step_counter += 1;
     let idx = state.step_counter;
+    let t = state.clock.as_ref().map(|c| c.now_rfc3339());
 
     state.trace.push(TraceEvent {
         step_index: idx,
         phase: phase.to_string(),
         route,
         payload,
+        t,
     });
 }
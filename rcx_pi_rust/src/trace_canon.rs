@@ -4,74 +4,197 @@
 //! IMPORTANT: This is a MIRROR of frozen Python v1 semantics, not authoritative.
 //! Python (`rcx_pi/trace_canon.py`) remains the canonical reference implementation.
 
+use std::collections::BTreeMap;
+
 use crate::json_value::JsonValue;
+use crate::trace::{route_to_string, TraceEvent};
+use crate::types::Mu;
 
 /// Trace event schema version.
-pub const TRACE_EVENT_V: i64 = 1;
+pub const TRACE_EVENT_V: i128 = 1;
 
 /// Canonical key order for trace events.
 pub const TRACE_EVENT_KEY_ORDER: &[&str] = &["v", "type", "i", "t", "mu", "meta"];
 
+/// The event field (`v`/`type`/`i`/`t`/`mu`/`meta`) a `TraceError` is
+/// attributable to, when it's attributable to exactly one.
+pub type TraceField = &'static str;
+
+/// Where a located event came from, for attaching to a `TraceError` raised
+/// while canonicalizing it. `source` is the raw JSONL line text, used to
+/// render `TraceError`'s caret pointer; it's `None` when only the line
+/// number (not the original text) is still available.
+#[derive(Debug, Clone, Copy)]
+pub struct EventLocation<'a> {
+    pub line: usize,
+    pub source: Option<&'a str>,
+}
+
+/// A diagnostic from `canon_event` / `canon_events` / `read_jsonl`.
+///
+/// Carries whatever location context was available at the point of failure
+/// (the JSONL line number, the event's index within the batch, and the
+/// offending field), so a caller can report e.g. `line 42, event[7].meta:
+/// must be an object` instead of a bare message. Any of the location fields
+/// may be `None` when that context doesn't apply (e.g. `canon_event` called
+/// directly on a single value has no batch index).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceError {
+    /// 1-based line number in the source JSONL, when known.
+    pub line: Option<usize>,
+    /// Index of the offending event within the batch being canonicalized.
+    pub event_index: Option<usize>,
+    /// The field the violation is attributable to.
+    pub field: Option<TraceField>,
+    /// Human-readable description of the violation.
+    pub message: String,
+    /// The raw source line, when known, so `Display` can point a caret at it.
+    pub source_line: Option<String>,
+}
+
+impl TraceError {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            line: None,
+            event_index: None,
+            field: None,
+            message: message.into(),
+            source_line: None,
+        }
+    }
+
+    fn field(mut self, field: TraceField) -> Self {
+        self.field = Some(field);
+        self
+    }
+
+    /// Attach the batch index and (if known) the source location, without
+    /// overwriting location a more specific error already set.
+    fn locate(mut self, event_index: usize, located: Option<EventLocation<'_>>) -> Self {
+        self.event_index = Some(event_index);
+        if let Some(EventLocation { line, source }) = located {
+            self.line.get_or_insert(line);
+            if let Some(source) = source {
+                self.source_line.get_or_insert_with(|| source.to_string());
+            }
+        }
+        self
+    }
+
+    /// Attach a source line directly, for failures (like a bad JSONL line)
+    /// that have no event index because no event was ever parsed.
+    fn at_line(mut self, line: usize, source: &str) -> Self {
+        self.line = Some(line);
+        self.source_line = Some(source.to_string());
+        self
+    }
+}
+
+impl std::fmt::Display for TraceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut loc = String::new();
+        match (self.line, self.event_index) {
+            (Some(l), Some(i)) => loc.push_str(&format!("line {l}, event[{i}]")),
+            (Some(l), None) => loc.push_str(&format!("line {l}")),
+            (None, Some(i)) => loc.push_str(&format!("event[{i}]")),
+            (None, None) => {}
+        }
+
+        let head = match self.field {
+            Some(field) if loc.is_empty() => field.to_string(),
+            Some(field) => format!("{loc}.{field}"),
+            None => loc,
+        };
+
+        if head.is_empty() {
+            write!(f, "{}", self.message)?;
+        } else {
+            write!(f, "{head}: {}", self.message)?;
+        }
+
+        if let Some(ref src) = self.source_line {
+            write!(f, "\n  {src}\n  {}", "^".repeat(src.len().max(1)))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for TraceError {}
+
+/// Lets `read_jsonl`/`canon_jsonl` still compose with call sites that
+/// propagate a bare `String` error (e.g. `replay_cli`'s `Result<_, String>`).
+impl From<TraceError> for String {
+    fn from(e: TraceError) -> Self {
+        e.to_string()
+    }
+}
+
 /// A canonicalized trace event.
 #[derive(Debug, Clone)]
 pub struct CanonEvent {
-    pub v: i64,
+    pub v: i128,
     pub event_type: String,
-    pub i: i64,
+    pub i: i128,
     pub t: Option<String>,
     pub mu: Option<JsonValue>,
     pub meta: Option<JsonValue>,
 }
 
 /// Parse and canonicalize a single trace event from JSON.
-pub fn canon_event(ev: &JsonValue) -> Result<CanonEvent, String> {
+pub fn canon_event(ev: &JsonValue) -> Result<CanonEvent, TraceError> {
     let obj = match ev {
         JsonValue::Object(o) => o,
-        _ => return Err("event must be an object".to_string()),
+        _ => return Err(TraceError::new("event must be an object")),
     };
 
-    // v: required, must be 1
+    // v: required, must be 1. Must be an integer literal (no `.`/`e`/`E`) so
+    // it round-trips exactly rather than through `f64`.
     let v = match obj.get("v") {
-        Some(JsonValue::Number(n)) => {
-            let v = *n as i64;
-            if v != TRACE_EVENT_V {
-                return Err(format!("event.v must be {}, got {}", TRACE_EVENT_V, v));
+        Some(JsonValue::Integer(n)) => {
+            if *n != TRACE_EVENT_V {
+                return Err(TraceError::new(format!(
+                    "must be {}, got {}",
+                    TRACE_EVENT_V, n
+                ))
+                .field("v"));
             }
-            v
+            *n
+        }
+        Some(JsonValue::Number(_)) => {
+            return Err(TraceError::new("must be an integer, not a float").field("v"))
         }
         None => TRACE_EVENT_V, // default
-        _ => return Err("event.v must be an integer".to_string()),
+        _ => return Err(TraceError::new("must be an integer").field("v")),
     };
 
     // type: required, non-empty string
     let event_type = match obj.get("type") {
         Some(JsonValue::String(s)) if !s.trim().is_empty() => s.clone(),
-        Some(JsonValue::String(_)) => {
-            return Err("event.type must be a non-empty string".to_string())
-        }
-        _ => return Err("event.type must be a non-empty string".to_string()),
+        _ => return Err(TraceError::new("must be a non-empty string").field("type")),
     };
 
-    // i: required, integer >= 0
+    // i: required, integer >= 0 (exact, not f64-rounded)
     let i = match obj.get("i") {
-        Some(JsonValue::Number(n)) => {
-            let i = *n as i64;
-            if i < 0 {
-                return Err("event.i must be >= 0".to_string());
+        Some(JsonValue::Integer(i)) => {
+            if *i < 0 {
+                return Err(TraceError::new("must be >= 0").field("i"));
             }
-            i
+            *i
+        }
+        Some(JsonValue::Number(_)) => {
+            return Err(TraceError::new("must be an integer, not a float").field("i"))
         }
-        _ => return Err("event.i must be an integer >= 0".to_string()),
+        _ => return Err(TraceError::new("must be an integer >= 0").field("i")),
     };
 
     // t: optional, non-empty string
     let t = match obj.get("t") {
         Some(JsonValue::String(s)) if !s.trim().is_empty() => Some(s.clone()),
         Some(JsonValue::String(_)) => {
-            return Err("event.t must be a non-empty string when provided".to_string())
+            return Err(TraceError::new("must be a non-empty string when provided").field("t"))
         }
         Some(JsonValue::Null) | None => None,
-        _ => return Err("event.t must be a string when provided".to_string()),
+        _ => return Err(TraceError::new("must be a string when provided").field("t")),
     };
 
     // mu: optional, any JSON (deep-sorted if dict/list)
@@ -84,7 +207,9 @@ pub fn canon_event(ev: &JsonValue) -> Result<CanonEvent, String> {
     let meta = match obj.get("meta") {
         Some(JsonValue::Null) | None => None,
         Some(v @ JsonValue::Object(_)) => Some(v.deep_sorted()),
-        Some(_) => return Err("event.meta must be an object when provided".to_string()),
+        Some(_) => {
+            return Err(TraceError::new("must be an object when provided").field("meta"))
+        }
     };
 
     Ok(CanonEvent {
@@ -98,21 +223,39 @@ pub fn canon_event(ev: &JsonValue) -> Result<CanonEvent, String> {
 }
 
 /// Canonicalize a sequence of events and enforce contiguous index ordering.
-pub fn canon_events(events: &[JsonValue]) -> Result<Vec<CanonEvent>, String> {
-    let mut out = Vec::with_capacity(events.len());
-    for ev in events {
-        out.push(canon_event(ev)?);
+///
+/// Each event's error is tagged with its index in `events`; use
+/// `canon_events_located` instead when the events came from a JSONL file and
+/// their source line numbers should be reported too.
+pub fn canon_events(events: &[JsonValue]) -> Result<Vec<CanonEvent>, TraceError> {
+    canon_events_located(events.iter().map(|ev| (ev, None)))
+}
+
+/// Like `canon_events`, but each event can carry its originating JSONL line
+/// number (and, if available, raw source text), so failures (including a
+/// contiguity violation) point at `line N, event[i].field` instead of just
+/// `event[i]`.
+pub fn canon_events_located<'a>(
+    events: impl IntoIterator<Item = (&'a JsonValue, Option<EventLocation<'a>>)>,
+) -> Result<Vec<CanonEvent>, TraceError> {
+    let mut out = Vec::new();
+    let mut locations = Vec::new();
+    for (index, (ev, located)) in events.into_iter().enumerate() {
+        out.push(canon_event(ev).map_err(|e| e.locate(index, located))?);
+        locations.push(located);
     }
 
     // Enforce contiguity
     if !out.is_empty() {
-        let expected: Vec<i64> = (0..out.len() as i64).collect();
-        let got: Vec<i64> = out.iter().map(|e| e.i).collect();
-        if got != expected {
-            return Err(format!(
+        let expected: Vec<i128> = (0..out.len() as i128).collect();
+        let got: Vec<i128> = out.iter().map(|e| e.i).collect();
+        if let Some(bad_index) = got.iter().zip(&expected).position(|(g, e)| g != e) {
+            return Err(TraceError::new(format!(
                 "event.i must be contiguous 0..n-1 in-order; got {:?}, expected {:?}",
                 got, expected
-            ));
+            ))
+            .field("i")
+            .locate(bad_index, locations[bad_index]));
         }
     }
 
@@ -175,27 +318,38 @@ fn json_escape_string(s: &str) -> String {
     out
 }
 
-/// Read JSONL file and return parsed events.
-pub fn read_jsonl(content: &str) -> Result<Vec<JsonValue>, String> {
+/// Read a JSONL file, returning each line's parsed event tagged with its
+/// 1-based line number so downstream diagnostics (`canon_events_located`)
+/// can report exactly where a violation came from.
+pub fn read_jsonl(content: &str) -> Result<Vec<(usize, JsonValue)>, TraceError> {
     let mut events = Vec::new();
     for (idx, line) in content.lines().enumerate() {
-        let line = line.trim();
-        if line.is_empty() {
+        let line_no = idx + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
             continue;
         }
-        let val = JsonValue::parse(line)
-            .map_err(|e| format!("line {}: invalid JSON: {}", idx + 1, e))?;
+        let val = JsonValue::parse(trimmed)
+            .map_err(|e| TraceError::new(format!("invalid JSON: {e}")).at_line(line_no, line))?;
         if !matches!(val, JsonValue::Object(_)) {
-            return Err(format!("line {}: expected object/dict per line", idx + 1));
+            return Err(TraceError::new("expected object/dict per line").at_line(line_no, line));
         }
-        events.push(val);
+        events.push((line_no, val));
     }
     Ok(events)
 }
 
 /// Canonicalize events and serialize to JSONL.
-pub fn canon_jsonl(events: &[JsonValue]) -> Result<String, String> {
-    let canon = canon_events(events)?;
+pub fn canon_jsonl(events: &[(usize, JsonValue)]) -> Result<String, TraceError> {
+    let canon = canon_events_located(events.iter().map(|(line, ev)| {
+        (
+            ev,
+            Some(EventLocation {
+                line: *line,
+                source: None,
+            }),
+        )
+    }))?;
     let mut out = String::new();
     for ev in canon {
         out.push_str(&canon_event_json(&ev));
@@ -204,6 +358,55 @@ pub fn canon_jsonl(events: &[JsonValue]) -> Result<String, String> {
     Ok(out)
 }
 
+/// Deterministically encode a `Mu` term as `JsonValue`, for embedding into a
+/// `CanonEvent`'s `mu` field: a `Sym` becomes a JSON string and a `Node`
+/// becomes a JSON array of its children encoded the same way.
+pub fn mu_to_json(mu: &Mu) -> JsonValue {
+    match mu {
+        Mu::Sym(s) => JsonValue::String(s.clone()),
+        Mu::Node(children) => JsonValue::Array(children.iter().map(mu_to_json).collect()),
+    }
+}
+
+/// Stable `type` string for a runtime `TraceEvent`'s route, e.g. `rcx.route.ra`.
+fn route_event_type(route: crate::trace::RouteKind) -> String {
+    format!("rcx.route.{}", route_to_string(route))
+}
+
+/// Convert one runtime `TraceEvent` into a `CanonEvent`.
+///
+/// `i` comes from `index` - the event's position in the batch being
+/// converted - rather than `step_index`: `step_index` is 1-based and only
+/// contiguous if every logged step survives to this conversion, whereas
+/// `canon_events`' contiguity check wants a 0-based run with no gaps.
+/// `phase` has no dedicated slot in the frozen schema, so it's preserved
+/// under `meta.phase` rather than dropped.
+pub fn canon_event_from_trace_event(index: usize, evt: &TraceEvent) -> CanonEvent {
+    let mut meta = BTreeMap::new();
+    meta.insert("phase".to_string(), JsonValue::String(evt.phase.clone()));
+    CanonEvent {
+        v: TRACE_EVENT_V,
+        event_type: route_event_type(evt.route),
+        i: index as i128,
+        t: evt.t.clone(),
+        mu: Some(mu_to_json(&evt.payload)),
+        meta: Some(JsonValue::Object(meta)),
+    }
+}
+
+/// Convert a full runtime trace into canonical JSONL text, one `CanonEvent`
+/// per line. Unlike `canon_jsonl`, this can't fail: events built via
+/// `canon_event_from_trace_event` are contiguous and well-formed by
+/// construction, so there's nothing for `canon_events` to reject.
+pub fn trace_to_canon_jsonl(trace: &[TraceEvent]) -> String {
+    let mut out = String::new();
+    for (index, evt) in trace.iter().enumerate() {
+        out.push_str(&canon_event_json(&canon_event_from_trace_event(index, evt)));
+        out.push('\n');
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -229,6 +432,36 @@ mod tests {
         assert_eq!(json, r#"{"v":1,"type":"trace.start","i":0}"#);
     }
 
+    #[test]
+    fn large_index_survives_exactly() {
+        // Above 2^53 an f64 can no longer represent consecutive integers
+        // exactly; canon_event must not round this through f64.
+        let input = r#"{"v":1,"type":"trace.start","i":9007199254740993}"#;
+        let val = JsonValue::parse(input).unwrap();
+        let ev = canon_event(&val).unwrap();
+        assert_eq!(ev.i, 9007199254740993);
+        assert_eq!(
+            canon_event_json(&ev),
+            r#"{"v":1,"type":"trace.start","i":9007199254740993}"#
+        );
+    }
+
+    #[test]
+    fn float_i_is_rejected() {
+        let input = r#"{"v":1,"type":"trace.start","i":0.0}"#;
+        let val = JsonValue::parse(input).unwrap();
+        let err = canon_event(&val).unwrap_err();
+        assert!(err.to_string().contains("float"));
+    }
+
+    #[test]
+    fn float_v_is_rejected() {
+        let input = r#"{"v":1.0,"type":"trace.start","i":0}"#;
+        let val = JsonValue::parse(input).unwrap();
+        let err = canon_event(&val).unwrap_err();
+        assert!(err.to_string().contains("float"));
+    }
+
     #[test]
     fn test_contiguity_check() {
         let events = vec![
@@ -237,6 +470,105 @@ mod tests {
         ];
         let result = canon_events(&events);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("contiguous"));
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("contiguous"));
+        assert_eq!(err.event_index, Some(1));
+    }
+
+    #[test]
+    fn error_reports_field_and_event_index_without_a_line() {
+        let events = vec![
+            JsonValue::parse(r#"{"v":1,"type":"a","i":0}"#).unwrap(),
+            JsonValue::parse(r#"{"v":1,"type":"b","i":1,"meta":"nope"}"#).unwrap(),
+        ];
+        let err = canon_events(&events).unwrap_err();
+        assert_eq!(err.field, Some("meta"));
+        assert_eq!(err.event_index, Some(1));
+        assert_eq!(err.line, None);
+        assert_eq!(err.to_string(), "event[1].meta: must be an object when provided");
+    }
+
+    #[test]
+    fn read_jsonl_reports_line_number_on_bad_json() {
+        let content = "{\"v\":1,\"type\":\"a\",\"i\":0}\nnot json\n";
+        let err = read_jsonl(content).unwrap_err();
+        assert_eq!(err.line, Some(2));
+        assert!(err.to_string().starts_with("line 2:"));
+        // caret-style pointer under the offending source line
+        assert!(err.to_string().contains("not json"));
+        assert!(err.to_string().contains('^'));
+    }
+
+    #[test]
+    fn canon_jsonl_contiguity_violation_reports_line_and_event_index() {
+        let content = "{\"v\":1,\"type\":\"a\",\"i\":0}\n{\"v\":1,\"type\":\"b\",\"i\":2}\n";
+        let events = read_jsonl(content).unwrap();
+        let err = canon_jsonl(&events).unwrap_err();
+        assert_eq!(err.event_index, Some(1));
+        assert_eq!(err.line, Some(2));
+        assert!(err.to_string().contains("line 2, event[1].i:"));
+    }
+
+    #[test]
+    fn mu_to_json_encodes_sym_as_string_and_node_as_array() {
+        let mu = Mu::with_head("PING", vec![Mu::Sym("a".to_string())]);
+        assert_eq!(
+            mu_to_json(&mu),
+            JsonValue::Array(vec![
+                JsonValue::String("PING".to_string()),
+                JsonValue::String("a".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn trace_event_converts_to_canon_event_with_route_type_and_phase_meta() {
+        use crate::trace::RouteKind;
+
+        let evt = TraceEvent {
+            step_index: 7,
+            phase: "engine_rule_to_ra".to_string(),
+            route: RouteKind::Ra,
+            payload: Mu::Sym("x".to_string()),
+            t: Some("1970-01-01T00:00:00Z".to_string()),
+        };
+        let ev = canon_event_from_trace_event(0, &evt);
+        assert_eq!(ev.event_type, "rcx.route.ra");
+        assert_eq!(ev.i, 0);
+        assert_eq!(ev.t.as_deref(), Some("1970-01-01T00:00:00Z"));
+        assert_eq!(ev.mu, Some(JsonValue::String("x".to_string())));
+        match ev.meta {
+            Some(JsonValue::Object(m)) => {
+                assert_eq!(m.get("phase"), Some(&JsonValue::String(evt.phase)));
+            }
+            other => panic!("expected meta object, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn trace_to_canon_jsonl_round_trips_through_canon_events() {
+        use crate::trace::RouteKind;
+
+        let trace = vec![
+            TraceEvent {
+                step_index: 1,
+                phase: "seed".to_string(),
+                route: RouteKind::Structural,
+                payload: Mu::Sym("seed".to_string()),
+                t: None,
+            },
+            TraceEvent {
+                step_index: 2,
+                phase: "rewrite".to_string(),
+                route: RouteKind::Rewrite,
+                payload: Mu::with_head("PONG", vec![]),
+                t: None,
+            },
+        ];
+
+        let jsonl = trace_to_canon_jsonl(&trace);
+        let events = read_jsonl(&jsonl).unwrap();
+        let canon = canon_jsonl(&events).unwrap();
+        assert_eq!(canon, jsonl);
     }
 }
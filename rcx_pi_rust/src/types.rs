@@ -7,6 +7,15 @@ pub enum Mu {
     Node(Vec<Mu>),
 }
 
+// No `Mu::Var` variant: pattern variables are plain `Sym`s distinguished by
+// naming convention (`pattern::is_capture`'s `?x`, `matching::is_var`'s
+// lowercase letter, `unify::is_var`'s uppercase letter) rather than by a
+// dedicated enum case, so a variable round-trips through the parser,
+// formatter, and codec without any of them needing a new match arm.
+// `pattern::mu_match_bind`/`substitute` bind and instantiate exactly the
+// way a `fn match_mu(pattern, term, bindings)` would, just keyed on `?x`
+// instead of a `Var(String)` case.
+
 impl Mu {
     /// Convenience constructor for a symbol &args => μ(sym, args...)
     pub fn with_head<S: Into<String>>(head: S, args: Vec<Mu>) -> Mu {
@@ -17,18 +26,57 @@ impl Mu {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum RuleAction {
     ToRa,
     ToLobe,
     ToSink,
     Rewrite(Mu), // <-- New! Allows Mu → Mu transformations
+    /// Like `Rewrite`, but the pattern is matched via `unify::unify` (its
+    /// single-uppercase-letter variable convention, e.g. `X`) instead of
+    /// `pattern::mu_match_bind`'s `?x` captures, and the template is
+    /// instantiated with `unify::apply_subst`. Lets one rule like pattern
+    /// `[NEWS, X]` / template `X` gate on the literal tag `NEWS` while
+    /// capturing and restructuring any payload, instead of needing one
+    /// literal rule per value.
+    ///
+    /// `unify`'s variables are a single uppercase letter (optionally with
+    /// trailing digits, e.g. `X1`), so multi-letter uppercase constants
+    /// like `STABLE`/`NEWS`/`UNSTABLE`/`PAIR` stay literal pattern heads
+    /// and don't collide with the variable convention.
+    RewriteTemplate(Mu),
+}
+
+/// Restricts when a rule's pattern is allowed to fire: once the pattern
+/// binds its captures (see `pattern::mu_match_bind`), `var`'s bound value
+/// must structurally equal `expected`, or the rule is skipped as if it
+/// hadn't matched at all.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Guard {
+    pub var: String,
+    pub expected: Mu,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct RcxRule {
     pub pattern: Mu,
+    pub guard: Option<Guard>,
     pub action: RuleAction,
+    /// Free-text trailing comment from the rule's source line, if any,
+    /// kept only so save/load round-trips don't lose it.
+    pub comment: Option<String>,
+}
+
+impl RcxRule {
+    /// Build a plain rule with no guard and no attached comment.
+    pub fn new(pattern: Mu, action: RuleAction) -> Self {
+        Self {
+            pattern,
+            guard: None,
+            action,
+            comment: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default)]
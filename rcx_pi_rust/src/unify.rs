@@ -6,16 +6,19 @@ use crate::types::Mu;
 /// Substitution: maps variable names → Mu terms.
 pub type Subst = HashMap<String, Mu>;
 
-/// Convention:
+/// Convention (mirrors `matching::is_var`'s lowercase one, upper-cased):
 ///   - `_` is a wildcard (matches anything, no binding)
-///   - Variables are symbols whose first char is ASCII uppercase (A–Z),
-///     e.g. `X`, `Y`, `Pair`, etc.
-///   - Everything else is a literal symbol.
+///   - Variables are a single ASCII uppercase letter optionally followed by
+///     ASCII digits, e.g. `X`, `Y`, `X1`, `X23`.
+///   - Everything else, including multi-letter uppercase symbols like
+///     `NEWS`/`STABLE`/`PAIR`, is a literal symbol - so those tag constants
+///     stay usable as literal pattern heads instead of capturing anything.
 fn is_var(name: &str) -> bool {
-    name.chars()
-        .next()
-        .map(|c| c.is_ascii_uppercase())
-        .unwrap_or(false)
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_uppercase() => chars.all(|c| c.is_ascii_digit()),
+        _ => false,
+    }
 }
 
 /// Public entry: try to unify `pattern` with `value`.
@@ -34,7 +37,7 @@ fn unify_with(subst: &mut Subst, pattern: &Mu, value: &Mu) -> bool {
         // `_` wildcard: matches anything, no binding.
         Mu::Sym(name) if name == "_" => true,
 
-        // Variable: uppercase-leading symbol, binds or checks consistent binding.
+        // Variable: single uppercase letter, binds or checks consistent binding.
         Mu::Sym(name) if is_var(name) => {
             if let Some(bound) = subst.get(name) {
                 bound == value
@@ -82,3 +85,20 @@ pub fn apply_subst(template: &Mu, subst: &Subst) -> Mu {
         Mu::Node(children) => Mu::Node(children.iter().map(|c| apply_subst(c, subst)).collect()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_var_accepts_a_single_uppercase_letter_but_not_a_multi_letter_literal() {
+        assert!(is_var("X"));
+        assert!(is_var("X1"));
+        assert!(is_var("X23"));
+        assert!(!is_var("_"));
+        assert!(!is_var("NEWS"));
+        assert!(!is_var("STABLE"));
+        assert!(!is_var("x"));
+        assert!(!is_var(""));
+    }
+}
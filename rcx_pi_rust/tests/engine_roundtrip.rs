@@ -10,29 +10,29 @@ fn make_news_program() -> RcxProgram {
     RcxProgram {
         rules: vec![
             // [NEWS,STABLE] -> r_a
-            RcxRule {
-                pattern: Mu::Node(vec![
+            RcxRule::new(
+                Mu::Node(vec![
                     Mu::Sym("NEWS".to_string()),
                     Mu::Sym("STABLE".to_string()),
                 ]),
-                action: RuleAction::ToRa,
-            },
+                RuleAction::ToRa,
+            ),
             // [NEWS,UNSTABLE] -> lobe
-            RcxRule {
-                pattern: Mu::Node(vec![
+            RcxRule::new(
+                Mu::Node(vec![
                     Mu::Sym("NEWS".to_string()),
                     Mu::Sym("UNSTABLE".to_string()),
                 ]),
-                action: RuleAction::ToLobe,
-            },
+                RuleAction::ToLobe,
+            ),
             // [NEWS,PARADOX] -> sink
-            RcxRule {
-                pattern: Mu::Node(vec![
+            RcxRule::new(
+                Mu::Node(vec![
                     Mu::Sym("NEWS".to_string()),
                     Mu::Sym("PARADOX".to_string()),
                 ]),
-                action: RuleAction::ToSink,
-            },
+                RuleAction::ToSink,
+            ),
         ],
     }
 }
@@ -79,10 +79,10 @@ fn news_program_routes_as_expected() {
 fn rewrite_rule_pings_to_pong() {
     // PING → PONG (rewrite), then structurally RA
     let program = RcxProgram {
-        rules: vec![RcxRule {
-            pattern: Mu::Sym("PING".to_string()),
-            action: RuleAction::Rewrite(Mu::Sym("PONG".to_string())),
-        }],
+        rules: vec![RcxRule::new(
+            Mu::Sym("PING".to_string()),
+            RuleAction::Rewrite(Mu::Sym("PONG".to_string())),
+        )],
     };
 
     let mut engine = Engine::new(program);
@@ -97,3 +97,57 @@ fn rewrite_rule_pings_to_pong() {
     assert!(state.lobes.is_empty());
     assert!(state.sink.is_empty());
 }
+
+#[test]
+fn rewrite_template_gates_on_a_multi_letter_uppercase_literal() {
+    // `unify`'s variables are a single uppercase letter (see
+    // `RuleAction::RewriteTemplate`), so a multi-letter uppercase constant
+    // like `STABLE` stays a literal pattern head: only `[PAIR, STABLE]`
+    // matches, and `[PAIR, UNSTABLE]` falls through to structural
+    // classification instead of also matching.
+    let program = RcxProgram {
+        rules: vec![RcxRule::new(
+            Mu::Node(vec![Mu::Sym("PAIR".to_string()), Mu::Sym("STABLE".to_string())]),
+            RuleAction::RewriteTemplate(Mu::Sym("STABLE".to_string())),
+        )],
+    };
+
+    let mut engine = Engine::new(program);
+    let mut state = RCXState::new();
+
+    let matching_route = engine.process_input(&mut state, Mu::Node(vec![
+        Mu::Sym("PAIR".to_string()),
+        Mu::Sym("STABLE".to_string()),
+    ]));
+    assert_eq!(matching_route, Some(RouteKind::Ra));
+    assert_eq!(state.ra, vec![Mu::Sym("STABLE".to_string())]);
+
+    let non_matching_route = engine.process_input(&mut state, Mu::Node(vec![
+        Mu::Sym("PAIR".to_string()),
+        Mu::Sym("UNSTABLE".to_string()),
+    ]));
+    assert_ne!(non_matching_route, Some(RouteKind::Ra));
+}
+
+#[test]
+fn rewrite_template_binds_a_single_uppercase_letter_as_a_variable() {
+    // [NEWS, X] -> X: NEWS gates literally (multi-letter), X captures and
+    // restructures any payload.
+    let program = RcxProgram {
+        rules: vec![RcxRule::new(
+            Mu::Node(vec![Mu::Sym("NEWS".to_string()), Mu::Sym("X".to_string())]),
+            RuleAction::RewriteTemplate(Mu::Sym("X".to_string())),
+        )],
+    };
+
+    let mut engine = Engine::new(program);
+    let mut state = RCXState::new();
+
+    let route = engine.process_input(&mut state, Mu::Node(vec![
+        Mu::Sym("NEWS".to_string()),
+        Mu::Sym("UNSTABLE".to_string()),
+    ]));
+
+    assert_eq!(route, Some(RouteKind::Ra));
+    assert_eq!(state.ra, vec![Mu::Sym("UNSTABLE".to_string())]);
+}
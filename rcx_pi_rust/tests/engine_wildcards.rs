@@ -14,10 +14,7 @@ fn node2(a: &str, b: &str) -> Mu {
 fn wildcard_news_sends_any_payload_to_sink() {
     // [news,_] -> sink
     let program = RcxProgram {
-        rules: vec![RcxRule {
-            pattern: node2("news", "_"),
-            action: RuleAction::ToSink,
-        }],
+        rules: vec![RcxRule::new(node2("news", "_"), RuleAction::ToSink)],
     };
 
     let mut engine = Engine::new(program);
@@ -36,10 +33,7 @@ fn wildcard_news_sends_any_payload_to_sink() {
 fn wildcard_omega_matches_nested_payloads() {
     // [omega,_] -> lobe
     let program = RcxProgram {
-        rules: vec![RcxRule {
-            pattern: node2("omega", "_"),
-            action: RuleAction::ToLobe,
-        }],
+        rules: vec![RcxRule::new(node2("omega", "_"), RuleAction::ToLobe)],
     };
 
     let mut engine = Engine::new(program);
@@ -0,0 +1,10 @@
+use std::path::Path;
+
+use rcx_pi_rust::harness::run_dir;
+
+#[test]
+fn golden_case_files_all_pass() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/cases");
+    let report = run_dir(&dir).unwrap();
+    assert!(report.all_passed(), "{report}");
+}